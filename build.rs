@@ -36,6 +36,39 @@ enum UrlKind {
     PulldownCmarkRepoSpec { filename: &'static str },
 }
 
+/// Which configuration to format the examples with
+#[cfg(feature = "gen-tests")]
+#[derive(Debug, Clone, Copy)]
+enum SpecConfig {
+    /// Format examples with the default configuration
+    Default,
+    /// Format examples with `FootnoteIndentation::TwoSpaces`
+    TwoSpaceFootnotes,
+}
+
+#[cfg(feature = "gen-tests")]
+impl SpecConfig {
+    /// Added to the name of the generated test file
+    fn file_suffix(&self) -> &'static str {
+        match self {
+            Self::Default => "",
+            Self::TwoSpaceFootnotes => "_two_space_footnotes",
+        }
+    }
+
+    /// Body of the `formatter()` function used by the generated tests
+    fn formatter(&self) -> Option<&'static str> {
+        match self {
+            Self::Default => None,
+            Self::TwoSpaceFootnotes => Some(
+                "let mut builder = markdown_fmt::FormatBuilder::default();\n    \
+                builder.footnote_indentation(markdown_fmt::FootnoteIndentation::TwoSpaces);\n    \
+                builder.build()",
+            ),
+        }
+    }
+}
+
 #[cfg(feature = "gen-tests")]
 fn generate_tests_markdown_tests() -> std::io::Result<()> {
     use std::fs::File;
@@ -49,11 +82,13 @@ fn generate_tests_markdown_tests() -> std::io::Result<()> {
             "",
             "./tests/spec/CommonMark/commonmark_v0_30_spec.json",
             UrlKind::CommonMarkSpec,
+            SpecConfig::Default,
         ),
         (
             "gfm_",
             "./tests/spec/GitHub/gfm_spec_v0_29_0_gfm_13.json",
             UrlKind::GitHubFlavoredMarkdownSpec,
+            SpecConfig::Default,
         ),
         (
             PULLDOWN_CMARK_PREFIX,
@@ -61,6 +96,15 @@ fn generate_tests_markdown_tests() -> std::io::Result<()> {
             UrlKind::PulldownCmarkRepoSpec {
                 filename: "footnotes.txt",
             },
+            SpecConfig::Default,
+        ),
+        (
+            PULLDOWN_CMARK_PREFIX,
+            "./tests/spec/pulldown_cmark/footnotes_v0_10_3.json",
+            UrlKind::PulldownCmarkRepoSpec {
+                filename: "footnotes.txt",
+            },
+            SpecConfig::TwoSpaceFootnotes,
         ),
         (
             PULLDOWN_CMARK_PREFIX,
@@ -68,6 +112,7 @@ fn generate_tests_markdown_tests() -> std::io::Result<()> {
             UrlKind::PulldownCmarkRepoSpec {
                 filename: "metadata_blocks.txt",
             },
+            SpecConfig::Default,
         ),
         (
             PULLDOWN_CMARK_PREFIX,
@@ -75,22 +120,24 @@ fn generate_tests_markdown_tests() -> std::io::Result<()> {
             UrlKind::PulldownCmarkRepoSpec {
                 filename: "heading_attrs.txt",
             },
+            SpecConfig::Default,
         ),
     ];
 
-    for (prefix, spec, url) in spec_files {
+    for (prefix, spec, url, config) in spec_files {
         // FIXME(ytmimi) switch to `cargo::` build script syntax when the Minimum Supported Rust
         // Version (MSRV) is 1.77.0 or higher.
         println!("cargo:rerun-if-changed={}", spec);
         let spec_file = spec.split('/').last().unwrap();
-        let mut output_file = PathBuf::from(format!("{test_folder}{spec_file}"));
-        output_file.set_extension("rs");
+        let spec_name = spec_file.trim_end_matches(".json");
+        let suffix = config.file_suffix();
+        let output_file = PathBuf::from(format!("{test_folder}{spec_name}{suffix}.rs"));
         println!("output_file: {}", output_file.display());
 
         let test_cases: Vec<TestCase<'_>> = serde_json::from_reader(File::open(&spec)?)?;
         let mut output = BufWriter::new(File::create(&output_file)?);
 
-        write_test_cases(&mut output, prefix, test_cases, url)
+        write_test_cases(&mut output, prefix, test_cases, url, config)
             .expect("generated test case successfully");
     }
 
@@ -104,6 +151,9 @@ struct TestCase<'a> {
     input: std::borrow::Cow<'a, str>,
     #[serde(rename(deserialize = "formattedMarkdown"))]
     output: Option<std::borrow::Cow<'a, str>>,
+    /// Only set when the output is different from `output`
+    #[serde(rename(deserialize = "formattedMarkdownTwoSpaceFootnotes"))]
+    two_space_footnotes_output: Option<std::borrow::Cow<'a, str>>,
     #[serde(rename(deserialize = "example"))]
     id: usize,
     section: std::borrow::Cow<'a, str>,
@@ -128,6 +178,7 @@ fn write_test_cases<W>(
     prefix: &str,
     test_cases: Vec<TestCase<'_>>,
     url: UrlKind,
+    config: SpecConfig,
 ) -> std::io::Result<()>
 where
     W: std::io::Write,
@@ -139,8 +190,15 @@ where
     writeln!(writer, "#![allow(missing_docs)]")?;
     writeln!(writer, "mod common;")?;
 
+    if let Some(formatter) = config.formatter() {
+        write!(
+            writer,
+            "\nfn formatter() -> markdown_fmt::MarkdownFormatter {{\n    {formatter}\n}}\n"
+        )?;
+    }
+
     for test_case in test_cases.into_iter() {
-        write_test_case(writer, prefix, test_case, url)?;
+        write_test_case(writer, prefix, test_case, url, config)?;
     }
     Ok(())
 }
@@ -151,6 +209,7 @@ fn write_test_case<W: std::io::Write>(
     prefix: &str,
     test_case: TestCase<'_>,
     url: UrlKind,
+    config: SpecConfig,
 ) -> std::io::Result<()> {
     let url = match url {
         UrlKind::CommonMarkSpec => {
@@ -200,13 +259,21 @@ fn {}markdown_{}_{}() {{
         test_case.test_macro,
     )?;
 
+    if config.formatter().is_some() {
+        write!(writer, "formatter(); ")?;
+    }
+
     let has_trailing_whitespace = input.lines().any(|l| l.ends_with(char::is_whitespace));
     if has_trailing_whitespace {
         write!(writer, "{:?}", input)?;
     } else {
         write!(writer, "r##\"{}\"##", input)?;
     }
-    if let Some(expected_output) = test_case.output {
+    let output = match config {
+        SpecConfig::Default => test_case.output,
+        SpecConfig::TwoSpaceFootnotes => test_case.two_space_footnotes_output.or(test_case.output),
+    };
+    if let Some(expected_output) = output {
         let has_trailing_whitespace = expected_output
             .lines()
             .any(|l| l.ends_with(char::is_whitespace));
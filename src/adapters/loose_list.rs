@@ -253,7 +253,12 @@ where
                 Event::Html(ref text) if !is_single_html_tag(text) => {
                     self.stashed_events
                         .push_back((current_event, current_range));
-                    maybe_push_start_paragraph!(self, Self);
+                    // The parser emits the leftover columns of a partially consumed tab as a
+                    // `Text` event within the HTML block. That text is still part of the HTML
+                    // block, so it shouldn't start a paragraph.
+                    if !matches!(self.inner.peek(), Some((Event::Text(_), _))) {
+                        maybe_push_start_paragraph!(self, Self);
+                    }
                 }
                 _ => {
                     self.stashed_events
@@ -445,7 +450,7 @@ mod test {
                     output.push(format!("event={event:?} range={range:?}").into())
                 }
                 output.push("".into());
-                std::fs::write(file, &output.join("\n")).unwrap();
+                std::fs::write(file, output.join("\n")).unwrap();
             } else {
                 for ((event, range), line) in events.zip(expected_events.trim().lines()) {
                     assert_eq!(format!("event={event:?} range={range:?}"), line);
@@ -463,7 +468,7 @@ mod test {
             let formatter = FormatBuilder::default().build();
             let fmt_state = FormatState::new(markdown, &formatter, adapted_events);
 
-            let output = fmt_state.format().unwrap();
+            let (output, _) = fmt_state.format_with_diagnostics().unwrap();
 
             // Get the events after formatting
             let pos_events = pulldown_cmark::Parser::new_ext(&output, options).collect::<Vec<_>>();
@@ -1,4 +1,9 @@
-use crate::config::Config;
+use crate::config::{
+    BlockQuoteStyle, BulletStyle, CodeFenceStyle, Config, EmphasisMarker,
+    FootnoteDefinitionPlacement, FootnoteIndentation, HardBreak, HeadingStyle, LineEnding,
+    LinkDefinitionPlacement, LinkStyle, LinkTitleStyle, ListIndent, ListSpacing,
+    OrderedListDelimiter, OrderedListNumbering, StrikethroughMarker, TableStyle, ThematicBreak,
+};
 
 /// Provides info that custom code block formatters can use
 /// when formatting code.
@@ -180,6 +185,1595 @@ impl FormatBuilder {
         self
     }
 
+    /// Configure how ordered list items are numbered.
+    /// By default, each item keeps the number it had in the source.
+    ///
+    /// # Setting [`ordered_list_numbering`](Self::ordered_list_numbering) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, OrderedListNumbering};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.ordered_list_numbering(OrderedListNumbering::Preserve);
+    ///
+    /// let input = "1. a\n1. b\n5. c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`ordered_list_numbering`](Self::ordered_list_numbering) to [`Sequential`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, OrderedListNumbering};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.ordered_list_numbering(OrderedListNumbering::Sequential);
+    ///
+    /// let input = "8. a\n1. b\n1. c";
+    /// let expected = "8. a\n9. b\n10. c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`ordered_list_numbering`](Self::ordered_list_numbering) to [`One`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, OrderedListNumbering};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.ordered_list_numbering(OrderedListNumbering::One);
+    ///
+    /// let input = "1. a\n2. b\n3. c";
+    /// let expected = "1. a\n1. b\n1. c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: OrderedListNumbering::Preserve
+    /// [`Sequential`]: OrderedListNumbering::Sequential
+    /// [`One`]: OrderedListNumbering::One
+    pub fn ordered_list_numbering(&mut self, numbering: OrderedListNumbering) -> &mut Self {
+        self.config.set_ordered_list_numbering(numbering);
+        self
+    }
+
+    /// Configure the delimiter that's written after the number of ordered list items.
+    /// By default, each list keeps the delimiter it had in the source.
+    ///
+    /// # Setting [`ordered_list_delimiter`](Self::ordered_list_delimiter) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, OrderedListDelimiter};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.ordered_list_delimiter(OrderedListDelimiter::Preserve);
+    ///
+    /// let input = "1) a\n2) b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`ordered_list_delimiter`](Self::ordered_list_delimiter) to [`Period`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, OrderedListDelimiter};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.ordered_list_delimiter(OrderedListDelimiter::Period);
+    ///
+    /// let input = "1) a\n2) b";
+    /// let expected = "1. a\n2. b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// Adjacent lists are only separate in the source because they use different delimiters.
+    /// When both lists would be written with the same delimiter an HTML comment is inserted
+    /// between them so they aren't merged into a single list.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, OrderedListDelimiter};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.ordered_list_delimiter(OrderedListDelimiter::Parenthesis);
+    ///
+    /// let input = "1. a\n2) b";
+    /// let expected = "1) a\n<!-- Keep adjacent lists separate -->\n2) b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: OrderedListDelimiter::Preserve
+    /// [`Period`]: OrderedListDelimiter::Period
+    pub fn ordered_list_delimiter(&mut self, delimiter: OrderedListDelimiter) -> &mut Self {
+        self.config.set_ordered_list_delimiter(delimiter);
+        self
+    }
+
+    /// Configure which characters are used for unordered list markers.
+    /// By default, each list keeps the marker it had in the source.
+    ///
+    /// # Setting [`bullet_style`](Self::bullet_style) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{BulletStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.bullet_style(BulletStyle::Preserve);
+    ///
+    /// let input = "* a\n  + b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`bullet_style`](Self::bullet_style) to [`Consistent`]
+    /// ```rust
+    /// # use markdown_fmt::{Bullet, BulletStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.bullet_style(BulletStyle::Consistent(Bullet::Hyphen));
+    ///
+    /// let input = "* a\n  + b";
+    /// let expected = "- a\n  - b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`bullet_style`](Self::bullet_style) to [`AlternateByDepth`]
+    /// ```rust
+    /// # use markdown_fmt::{Bullet, BulletStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.bullet_style(BulletStyle::AlternateByDepth(vec![
+    ///     Bullet::Hyphen,
+    ///     Bullet::Asterisk,
+    /// ]));
+    ///
+    /// let input = "+ a\n  + b\n    + c";
+    /// let expected = "- a\n  * b\n    - c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// Adjacent lists are only separate in the source because they use different markers.
+    /// When both lists would be written with the same marker an HTML comment is inserted
+    /// between them so they aren't merged into a single list.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{Bullet, BulletStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.bullet_style(BulletStyle::Consistent(Bullet::Hyphen));
+    ///
+    /// let input = "* a\n+ b";
+    /// let expected = "- a\n<!-- Keep adjacent lists separate -->\n- b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: BulletStyle::Preserve
+    /// [`Consistent`]: BulletStyle::Consistent
+    /// [`AlternateByDepth`]: BulletStyle::AlternateByDepth
+    pub fn bullet_style(&mut self, style: BulletStyle) -> &mut Self {
+        self.config.set_bullet_style(style);
+        self
+    }
+
+    /// Configure the delimiter used for emphasis. By default, the delimiter from the source is kept.
+    ///
+    /// # Setting [`emphasis_marker`](Self::emphasis_marker) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{EmphasisMarker, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.emphasis_marker(EmphasisMarker::Preserve);
+    ///
+    /// let input = "*a* _b_";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`emphasis_marker`](Self::emphasis_marker) to [`Underscore`]
+    /// ```rust
+    /// # use markdown_fmt::{EmphasisMarker, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.emphasis_marker(EmphasisMarker::Underscore);
+    ///
+    /// let input = "*a* _b_";
+    /// let expected = "_a_ _b_";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// The source delimiter is kept whenever the configured delimiter could change the meaning
+    /// of the document. For example, `_` can't be used for emphasis within a word.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{EmphasisMarker, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.emphasis_marker(EmphasisMarker::Underscore);
+    ///
+    /// let input = "snake*case*";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    ///
+    /// [`Preserve`]: EmphasisMarker::Preserve
+    /// [`Underscore`]: EmphasisMarker::Underscore
+    pub fn emphasis_marker(&mut self, marker: EmphasisMarker) -> &mut Self {
+        self.config.set_emphasis_marker(marker);
+        self
+    }
+
+    /// Configure the delimiter used for strong emphasis. By default, the delimiter from the
+    /// source is kept.
+    ///
+    /// # Setting [`strong_marker`](Self::strong_marker) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{EmphasisMarker, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.strong_marker(EmphasisMarker::Preserve);
+    ///
+    /// let input = "**a** __b__";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`strong_marker`](Self::strong_marker) to [`Asterisk`]
+    /// ```rust
+    /// # use markdown_fmt::{EmphasisMarker, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.strong_marker(EmphasisMarker::Asterisk);
+    ///
+    /// let input = "**a** __b__";
+    /// let expected = "**a** **b**";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// Just like [`emphasis_marker`](Self::emphasis_marker), the source delimiter is kept
+    /// whenever the configured delimiter could change the meaning of the document.
+    ///
+    /// [`Preserve`]: EmphasisMarker::Preserve
+    /// [`Asterisk`]: EmphasisMarker::Asterisk
+    pub fn strong_marker(&mut self, marker: EmphasisMarker) -> &mut Self {
+        self.config.set_strong_marker(marker);
+        self
+    }
+
+    /// Configure the delimiter used for strikethrough. By default, the delimiter from the
+    /// source is kept.
+    ///
+    /// # Setting [`strikethrough_marker`](Self::strikethrough_marker) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, StrikethroughMarker};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.strikethrough_marker(StrikethroughMarker::Preserve);
+    ///
+    /// let input = "~a~ ~~b~~";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`strikethrough_marker`](Self::strikethrough_marker) to [`DoubleTilde`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, StrikethroughMarker};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.strikethrough_marker(StrikethroughMarker::DoubleTilde);
+    ///
+    /// let input = "~a~ ~~b~~";
+    /// let expected = "~~a~~ ~~b~~";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: StrikethroughMarker::Preserve
+    /// [`DoubleTilde`]: StrikethroughMarker::DoubleTilde
+    pub fn strikethrough_marker(&mut self, marker: StrikethroughMarker) -> &mut Self {
+        self.config.set_strikethrough_marker(marker);
+        self
+    }
+
+    /// Configure whether headings are written as ATX headings (`# Heading`) or setext headings
+    /// (`Heading` underlined with `===` or `---`). By default, each heading keeps its style.
+    ///
+    /// # Setting [`heading_style`](Self::heading_style) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HeadingStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.heading_style(HeadingStyle::Preserve);
+    ///
+    /// let input = "# H1\n\nH2\n--";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`heading_style`](Self::heading_style) to [`Atx`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HeadingStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.heading_style(HeadingStyle::Atx);
+    ///
+    /// let input = "H1\n==\n\nH2\n--";
+    /// let expected = "# H1\n\n## H2";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// Setext headings that span multiple lines can't be written as ATX headings,
+    /// so they're left as setext headings.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HeadingStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.heading_style(HeadingStyle::Atx);
+    ///
+    /// let input = "Multi-line\nH1\n===";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`heading_style`](Self::heading_style) to [`Setext`]
+    ///
+    /// The underline matches the display width of the heading's last line.
+    /// Level 3 through 6 headings are always written as ATX headings.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HeadingStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.heading_style(HeadingStyle::Setext);
+    ///
+    /// let input = "# Heading 1\n\n## Heading 2\n\n### Heading 3";
+    /// let expected = "Heading 1\n=========\n\nHeading 2\n---------\n\n### Heading 3";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: HeadingStyle::Preserve
+    /// [`Atx`]: HeadingStyle::Atx
+    /// [`Setext`]: HeadingStyle::Setext
+    pub fn heading_style(&mut self, style: HeadingStyle) -> &mut Self {
+        self.config.set_heading_style(style);
+        self
+    }
+
+    /// Configure whether ATX headings are written with a closing sequence of `#` that matches
+    /// the heading level. By default the closing sequence is removed.
+    ///
+    /// # Setting [`close_atx_headings`](Self::close_atx_headings) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.close_atx_headings(false);
+    ///
+    /// let input = "## Heading ##";
+    /// let expected = "## Heading";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`close_atx_headings`](Self::close_atx_headings) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.close_atx_headings(true);
+    ///
+    /// let input = "# Heading 1\n\n## Heading 2 # {#id}";
+    /// let expected = "# Heading 1 #\n\n## Heading 2 ## {#id}";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn close_atx_headings(&mut self, close: bool) -> &mut Self {
+        self.config.set_close_atx_headings(close);
+        self
+    }
+
+    /// Configure the fences used for fenced code blocks. By default, each code block keeps the
+    /// fence it had in the source.
+    ///
+    /// When fences are normalized, the shortest fence that's still longer than any run of the
+    /// fence character in the code block is used. Fences are at least three characters long.
+    ///
+    /// # Setting [`code_fence_style`](Self::code_fence_style) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{CodeFenceStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.code_fence_style(CodeFenceStyle::Preserve);
+    ///
+    /// let input = "~~~~~rust\nfn main() {}\n~~~~~";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`code_fence_style`](Self::code_fence_style) to [`Backtick`]
+    /// ```rust
+    /// # use markdown_fmt::{CodeFenceStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.code_fence_style(CodeFenceStyle::Backtick);
+    ///
+    /// let input = "~~~~~rust\nfn main() {}\n~~~~~\n\n~~~markdown\n```\ncode\n```\n~~~";
+    /// let expected = "```rust\nfn main() {}\n```\n\n````markdown\n```\ncode\n```\n````";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`code_fence_style`](Self::code_fence_style) to [`Tilde`]
+    /// ```rust
+    /// # use markdown_fmt::{CodeFenceStyle, FormatBuilder};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.code_fence_style(CodeFenceStyle::Tilde);
+    ///
+    /// let input = "````rust\nfn main() {}\n````";
+    /// let expected = "~~~rust\nfn main() {}\n~~~";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: CodeFenceStyle::Preserve
+    /// [`Backtick`]: CodeFenceStyle::Backtick
+    /// [`Tilde`]: CodeFenceStyle::Tilde
+    pub fn code_fence_style(&mut self, style: CodeFenceStyle) -> &mut Self {
+        self.config.set_code_fence_style(style);
+        self
+    }
+
+    /// Configure whether indented code blocks are rewritten as fenced code blocks.
+    /// By default indented code blocks are left as is.
+    ///
+    /// When set to `Some(info_string)`, the `info_string` is written after the opening code fence.
+    /// Use `Some("")` for fenced code blocks without an info string. The code is then formatted
+    /// by the [`code_block_formatter`](Self::code_block_formatter) just like any other fenced
+    /// code block. The code fence follows the [`code_fence_style`](Self::code_fence_style),
+    /// and uses backticks if code fences are preserved.
+    ///
+    /// # Setting [`fence_indented_code_blocks`](Self::fence_indented_code_blocks) to `None` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.fence_indented_code_blocks(None);
+    ///
+    /// let input = "    fn main() {}";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`fence_indented_code_blocks`](Self::fence_indented_code_blocks) to `Some(info_string)`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.fence_indented_code_blocks(Some("rust"));
+    ///
+    /// let input = "    fn main() {}";
+    /// let expected = "```rust\nfn main() {}\n```";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn fence_indented_code_blocks(&mut self, info_string: Option<&str>) -> &mut Self {
+        self.config
+            .set_fence_indented_code_blocks(info_string.map(str::to_owned));
+        self
+    }
+
+    /// Configure how thematic breaks are written. By default, each thematic break keeps the
+    /// text it had in the source.
+    ///
+    /// # Setting [`thematic_break`](Self::thematic_break) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ThematicBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.thematic_break(ThematicBreak::Preserve);
+    ///
+    /// let input = "***\n\n_ _ _";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`thematic_break`](Self::thematic_break) to [`Canonical`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ThematicBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.thematic_break(ThematicBreak::Canonical("---".into()));
+    ///
+    /// let input = "***\n\n_ _ _";
+    /// let expected = "---\n\n---";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// A `---` directly after a paragraph would turn the paragraph into a setext heading,
+    /// so a blank line is added between them.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ThematicBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.thematic_break(ThematicBreak::Canonical("---".into()));
+    ///
+    /// let input = "paragraph\n***";
+    /// let expected = "paragraph\n\n---";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// A `---` that's directly followed by another line starts a metadata block when a later
+    /// `---` line ends it. The thematic break from the source is kept whenever a `---` could
+    /// start a metadata block, or end one started by an earlier `---` line.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ThematicBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.thematic_break(ThematicBreak::Canonical("---".into()));
+    ///
+    /// let input = "***\n# Heading\n\n---\ntext\n\n* * *";
+    /// let expected = "***\n# Heading\n\n---\ntext\n\n* * *";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`thematic_break`](Self::thematic_break) to [`FillWidth`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ThematicBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.max_width(Some(20)).thematic_break(ThematicBreak::FillWidth('*'));
+    ///
+    /// let input = "---\n\n> ___";
+    /// let expected = "********************\n\n> ******************";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: ThematicBreak::Preserve
+    /// [`Canonical`]: ThematicBreak::Canonical
+    /// [`FillWidth`]: ThematicBreak::FillWidth
+    pub fn thematic_break(&mut self, style: ThematicBreak) -> &mut Self {
+        self.config.set_thematic_break(style);
+        self
+    }
+
+    /// Configure where link reference definitions are written. By default, each definition
+    /// stays where it was in the source.
+    ///
+    /// Definitions nested in lists, block quotes, and footnote definitions are moved too.
+    /// Link reference definitions apply to the whole document, so links still resolve to the
+    /// same destination after their definitions are moved. The only exception are definitions
+    /// that follow a blank line at the end of a list's last item. Moving those could turn a
+    /// loose list into a tight list, so they stay where they are.
+    ///
+    /// # Setting [`link_definition_placement`](Self::link_definition_placement) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkDefinitionPlacement};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_definition_placement(LinkDefinitionPlacement::Preserve);
+    ///
+    /// let input = "[a]: /a\n\n# Section\n[b]\n\n[b]: /b\n\nMore [a].";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`link_definition_placement`](Self::link_definition_placement) to [`EndOfDocument`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkDefinitionPlacement};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_definition_placement(LinkDefinitionPlacement::EndOfDocument);
+    ///
+    /// let input = "[a]: /a\n\n# Section\n[b]\n\n> [b]: /b\n\nMore [a].";
+    /// let expected = "# Section\n[b]\n\n>\n\nMore [a].\n\n[a]: /a\n[b]: /b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`link_definition_placement`](Self::link_definition_placement) to [`EndOfSection`]
+    ///
+    /// Sections end right before the next heading that isn't nested in a block quote, list, or
+    /// footnote definition.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkDefinitionPlacement};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_definition_placement(LinkDefinitionPlacement::EndOfSection);
+    ///
+    /// let input = "# One\n[a]: /a\n\n[a]\n# Two\n- [b]: /b\n  [b]";
+    /// let expected = "# One\n\n[a]\n\n[a]: /a\n\n# Two\n- [b]\n\n[b]: /b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: LinkDefinitionPlacement::Preserve
+    /// [`EndOfDocument`]: LinkDefinitionPlacement::EndOfDocument
+    /// [`EndOfSection`]: LinkDefinitionPlacement::EndOfSection
+    pub fn link_definition_placement(&mut self, placement: LinkDefinitionPlacement) -> &mut Self {
+        self.config.set_link_definition_placement(placement);
+        self
+    }
+
+    /// Configure whether consecutive link reference definitions are sorted by their label.
+    /// By default definitions are written in the order they were defined.
+    ///
+    /// Labels are compared the same way links are matched to definitions, ignoring case and
+    /// extra whitespace. Definitions with the same label keep their relative order so that the
+    /// first definition still wins.
+    ///
+    /// # Setting [`sort_link_definitions`](Self::sort_link_definitions) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.sort_link_definitions(false);
+    ///
+    /// let input = "[c]: /c\n[B]: /b\n[a]: /a";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`sort_link_definitions`](Self::sort_link_definitions) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.sort_link_definitions(true);
+    ///
+    /// let input = "[c]: /c\n[B]: /b\n[a]: /a";
+    /// let expected = "[a]: /a\n[B]: /b\n[c]: /c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn sort_link_definitions(&mut self, sort: bool) -> &mut Self {
+        self.config.set_sort_link_definitions(sort);
+        self
+    }
+
+    /// Configure whether duplicate link reference definitions are removed. By default every
+    /// definition is kept.
+    ///
+    /// Links always use the first definition for a label, so any later definition with the
+    /// same label can never be used. Duplicates that follow a blank line at the end of a list's
+    /// last item are kept, since removing them could turn a loose list into a tight list.
+    ///
+    /// # Setting [`deduplicate_link_definitions`](Self::deduplicate_link_definitions) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.deduplicate_link_definitions(false);
+    ///
+    /// let input = "[a]: /first\n[A]: /second";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`deduplicate_link_definitions`](Self::deduplicate_link_definitions) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.deduplicate_link_definitions(true);
+    ///
+    /// let input = "[a]: /first\n[A]: /second";
+    /// let expected = "[a]: /first";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn deduplicate_link_definitions(&mut self, deduplicate: bool) -> &mut Self {
+        self.config.set_deduplicate_link_definitions(deduplicate);
+        self
+    }
+
+    /// Configure whether link reference definition labels are normalized. By default labels are
+    /// written the way they were defined.
+    ///
+    /// Normalized labels are lowercase, and any whitespace between words is collapsed into a
+    /// single space. Links match labels without considering case or extra whitespace, so they
+    /// still use the same definition.
+    ///
+    /// # Setting [`normalize_link_labels`](Self::normalize_link_labels) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.normalize_link_labels(false);
+    ///
+    /// let input = "[Foo   Bar]: /url";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`normalize_link_labels`](Self::normalize_link_labels) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.normalize_link_labels(true);
+    ///
+    /// let input = "[Foo   Bar]: /url";
+    /// let expected = "[foo bar]: /url";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn normalize_link_labels(&mut self, normalize: bool) -> &mut Self {
+        self.config.set_normalize_link_labels(normalize);
+        self
+    }
+
+    /// Configure which quotes wrap link reference definition titles. By default each title keeps
+    /// the quotes it had in the source.
+    ///
+    /// # Setting [`link_title_style`](Self::link_title_style) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkTitleStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_title_style(LinkTitleStyle::Preserve);
+    ///
+    /// let input = "[a]: /a 'title'\n[b]: /b (title)";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`link_title_style`](Self::link_title_style) to [`DoubleQuote`]
+    ///
+    /// Quotes inside the title are escaped when needed.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkTitleStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_title_style(LinkTitleStyle::DoubleQuote);
+    ///
+    /// let input = "[a]: /a 'a \"title\"'\n[b]: /b (title)";
+    /// let expected = "[a]: /a \"a \\\"title\\\"\"\n[b]: /b \"title\"";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`link_title_style`](Self::link_title_style) to [`SingleQuote`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkTitleStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_title_style(LinkTitleStyle::SingleQuote);
+    ///
+    /// let input = "[a]: /a \"title\"\n[b]: /b (title)";
+    /// let expected = "[a]: /a 'title'\n[b]: /b 'title'";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`link_title_style`](Self::link_title_style) to [`Parentheses`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkTitleStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_title_style(LinkTitleStyle::Parentheses);
+    ///
+    /// let input = "[a]: /a \"a (title)\"\n[b]: /b 'title'";
+    /// let expected = "[a]: /a (a \\(title\\))\n[b]: /b (title)";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: LinkTitleStyle::Preserve
+    /// [`DoubleQuote`]: LinkTitleStyle::DoubleQuote
+    /// [`SingleQuote`]: LinkTitleStyle::SingleQuote
+    /// [`Parentheses`]: LinkTitleStyle::Parentheses
+    pub fn link_title_style(&mut self, style: LinkTitleStyle) -> &mut Self {
+        self.config.set_link_title_style(style);
+        self
+    }
+
+    /// Configure whether links and images are written inline or as reference links. By default
+    /// each link keeps the style it had in the source.
+    ///
+    /// # Setting [`link_style`](Self::link_style) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_style(LinkStyle::Preserve);
+    ///
+    /// let input = "[inline](/a) and [reference]\n\n[reference]: /b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`link_style`](Self::link_style) to [`Inline`]
+    ///
+    /// Reference, collapsed, and shortcut links are written as inline links. Definitions that
+    /// are no longer used are removed.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_style(LinkStyle::Inline);
+    ///
+    /// let input = "[a link][a] and ![an image]\n\n[a]: /a \"title\"\n[an image]: /image.png";
+    /// let expected = "[a link](/a \"title\") and ![an image](/image.png)";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`link_style`](Self::link_style) to [`Reference`]
+    ///
+    /// Inline links are written as reference links. Links to the same destination and title
+    /// share a label, and existing definitions are reused. New definitions use numeric labels
+    /// that don't clash with other labels in the document, and they're written at the end of
+    /// the document unless [`link_definition_placement`](Self::link_definition_placement) says
+    /// otherwise.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LinkStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.link_style(LinkStyle::Reference);
+    ///
+    /// let input = "[a](/a) [b](/b 'title') [c](/a) [d](/d) [1]\n\n[d]: /d";
+    /// let expected = "[a][2] [b][3] [c][2] [d][d] [1]\n\n[d]: /d\n\n[2]: /a\n[3]: /b 'title'";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: LinkStyle::Preserve
+    /// [`Inline`]: LinkStyle::Inline
+    /// [`Reference`]: LinkStyle::Reference
+    pub fn link_style(&mut self, style: LinkStyle) -> &mut Self {
+        self.config.set_link_style(style);
+        self
+    }
+
+    /// Configure whether link reference definitions that no link or image uses are removed.
+    /// By default they're kept.
+    ///
+    /// Use [`MarkdownFormatter::format_with_diagnostics`] to find unused definitions without
+    /// removing them.
+    ///
+    /// # Setting [`remove_unused_link_definitions`](Self::remove_unused_link_definitions) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_unused_link_definitions(false);
+    ///
+    /// let input = "[used]\n\n[used]: /a\n[unused]: /b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`remove_unused_link_definitions`](Self::remove_unused_link_definitions) to `true`
+    ///
+    /// Definitions that come after a blank line at the end of a list's last item are kept,
+    /// since removing them could change the list from loose to tight.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_unused_link_definitions(true);
+    ///
+    /// let input = "[used]\n\n[used]: /a\n[unused]: /b";
+    /// let expected = "[used]\n\n[used]: /a";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`MarkdownFormatter::format_with_diagnostics`]: crate::MarkdownFormatter::format_with_diagnostics
+    pub fn remove_unused_link_definitions(&mut self, remove: bool) -> &mut Self {
+        self.config.set_remove_unused_link_definitions(remove);
+        self
+    }
+
+    /// Configure whether [`MarkdownFormatter::format_with_diagnostics`] reports shortcut
+    /// references, like `[this]`, whose label doesn't match any link reference definition.
+    /// By default they aren't reported, since text in square brackets, like `array[0]` or `[sic]`,
+    /// is usually prose and not a broken link.
+    ///
+    /// Full and collapsed references, like `[text][label]` and `[label][]`, are always reported.
+    ///
+    /// # Setting [`report_undefined_shortcut_references`](Self::report_undefined_shortcut_references) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.report_undefined_shortcut_references(false);
+    ///
+    /// let input = "See [missing] and [missing][].";
+    /// let (_, diagnostics) = builder.build().format_with_diagnostics(input).unwrap();
+    /// let reported = diagnostics.iter().map(|d| &input[d.range()]).collect::<Vec<_>>();
+    /// assert_eq!(reported, ["[missing][]"])
+    /// ```
+    /// ---
+    /// # Setting [`report_undefined_shortcut_references`](Self::report_undefined_shortcut_references) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.report_undefined_shortcut_references(true);
+    ///
+    /// let input = "See [missing] and [missing][].";
+    /// let (_, diagnostics) = builder.build().format_with_diagnostics(input).unwrap();
+    /// let reported = diagnostics.iter().map(|d| &input[d.range()]).collect::<Vec<_>>();
+    /// assert_eq!(reported, ["[missing]", "[missing][]"])
+    /// ```
+    ///
+    /// [`MarkdownFormatter::format_with_diagnostics`]: crate::MarkdownFormatter::format_with_diagnostics
+    pub fn report_undefined_shortcut_references(&mut self, report: bool) -> &mut Self {
+        self.config.set_report_undefined_shortcut_references(report);
+        self
+    }
+
+    /// Configure where footnote definitions are written. By default each definition stays where
+    /// it was in the source.
+    ///
+    /// # Setting [`footnote_definition_placement`](Self::footnote_definition_placement) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, FootnoteDefinitionPlacement};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.footnote_definition_placement(FootnoteDefinitionPlacement::Preserve);
+    ///
+    /// let input = "[^b]:\n    B\n\nSee[^a] and[^b].\n\n[^a]:\n    A";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`footnote_definition_placement`](Self::footnote_definition_placement) to [`EndOfDocument`]
+    ///
+    /// Definitions are written in the order that the footnotes are first referenced, and
+    /// definitions that are never referenced come last.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, FootnoteDefinitionPlacement};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.footnote_definition_placement(FootnoteDefinitionPlacement::EndOfDocument);
+    ///
+    /// let input = "[^b]:\n    B\n\nSee[^a] and[^b].\n\n[^unused]:\n    C\n\n[^a]:\n    A";
+    /// let expected = "See[^a] and[^b].\n\n[^a]:\n    A\n\n[^b]:\n    B\n\n[^unused]:\n    C";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: FootnoteDefinitionPlacement::Preserve
+    /// [`EndOfDocument`]: FootnoteDefinitionPlacement::EndOfDocument
+    pub fn footnote_definition_placement(
+        &mut self,
+        placement: FootnoteDefinitionPlacement,
+    ) -> &mut Self {
+        self.config.set_footnote_definition_placement(placement);
+        self
+    }
+
+    /// Configure whether footnotes with numeric labels, like `[^1]`, are renumbered in the order
+    /// that they're first referenced. By default labels aren't changed.
+    ///
+    /// # Setting [`renumber_footnotes`](Self::renumber_footnotes) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.renumber_footnotes(false);
+    ///
+    /// let input = "See[^3] and[^1].\n\n[^1]:\n    One\n\n[^3]:\n    Three";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`renumber_footnotes`](Self::renumber_footnotes) to `true`
+    ///
+    /// Footnotes that aren't referenced are numbered last, and footnotes with other labels
+    /// keep their labels. Numbers that already appear in text like `[^2]` aren't used, since
+    /// defining them would turn that text into a footnote reference.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.renumber_footnotes(true);
+    ///
+    /// let input = "See[^3],[^note], and[^1], but not [^2].\n\n[^1]:\n    One\n\n[^3]:\n    Three\n\n[^note]:\n    Note";
+    /// let expected = "See[^1],[^note], and[^3], but not [^2].\n\n[^3]:\n    One\n\n[^1]:\n    Three\n\n[^note]:\n    Note";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn renumber_footnotes(&mut self, renumber: bool) -> &mut Self {
+        self.config.set_renumber_footnotes(renumber);
+        self
+    }
+
+    /// Configure whether footnote definitions that are never referenced are removed. By default
+    /// they're kept.
+    ///
+    /// Use [`MarkdownFormatter::format_with_diagnostics`] to find unused footnote definitions
+    /// without removing them.
+    ///
+    /// # Setting [`remove_unused_footnote_definitions`](Self::remove_unused_footnote_definitions) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_unused_footnote_definitions(false);
+    ///
+    /// let input = "See[^a].\n\n[^a]:\n    A\n\n[^unused]:\n    B";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`remove_unused_footnote_definitions`](Self::remove_unused_footnote_definitions) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_unused_footnote_definitions(true);
+    ///
+    /// let input = "See[^a].\n\n[^a]:\n    A\n\n[^unused]:\n    B";
+    /// let expected = "See[^a].\n\n[^a]:\n    A";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`MarkdownFormatter::format_with_diagnostics`]: crate::MarkdownFormatter::format_with_diagnostics
+    pub fn remove_unused_footnote_definitions(&mut self, remove: bool) -> &mut Self {
+        self.config.set_remove_unused_footnote_definitions(remove);
+        self
+    }
+
+    /// Configure how the content of footnote definitions is indented.
+    ///
+    /// # Setting [`footnote_indentation`](Self::footnote_indentation) to [`FourSpaces`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, FootnoteIndentation};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.footnote_indentation(FootnoteIndentation::FourSpaces);
+    ///
+    /// let input = "[^a]: A footnote\n  that spans lines.";
+    /// let expected = "[^a]:\n    A footnote\n    that spans lines.";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`footnote_indentation`](Self::footnote_indentation) to [`TwoSpaces`]
+    ///
+    /// Only footnote definitions that contain a single paragraph are indented by two spaces.
+    /// Footnote definitions with more than one block, or with a block other than a paragraph,
+    /// keep four-space indentation, since two spaces of indentation would end the footnote
+    /// definition.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, FootnoteIndentation};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.footnote_indentation(FootnoteIndentation::TwoSpaces);
+    ///
+    /// let input = "[^a]:\n    A footnote\n    that spans lines.\n\n[^b]: Paragraph\n\n    - list";
+    /// let expected = "[^a]: A footnote\n  that spans lines.\n\n[^b]:\n    Paragraph\n\n    - list";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`FourSpaces`]: FootnoteIndentation::FourSpaces
+    /// [`TwoSpaces`]: FootnoteIndentation::TwoSpaces
+    pub fn footnote_indentation(&mut self, indentation: FootnoteIndentation) -> &mut Self {
+        self.config.set_footnote_indentation(indentation);
+        self
+    }
+
+    /// Configure how tables are laid out.
+    ///
+    /// # Setting [`table_style`](Self::table_style) to [`Aligned`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, TableStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.table_style(TableStyle::Aligned);
+    ///
+    /// let input = "| a | b |\n|-|:-:|\n| longer cell | c |";
+    /// let expected = "| a           | b   |\n| ----------- | :-: |\n| longer cell | c   |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`table_style`](Self::table_style) to [`Compact`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, TableStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.table_style(TableStyle::Compact);
+    ///
+    /// let input = "| a           | b   |\n| ----------- | :-: |\n| longer cell |     |";
+    /// let expected = "| a | b |\n| - | :-: |\n| longer cell | |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`table_style`](Self::table_style) to [`AlignedUnlessTooWide`]
+    ///
+    /// Tables are compact when the aligned table wouldn't fit within the
+    /// [`max_width`](Self::max_width), including any indentation from lists or block quotes.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, TableStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.max_width(Some(20)).table_style(TableStyle::AlignedUnlessTooWide);
+    ///
+    /// let input = "| a | b |\n|-|-|\n| c | d |\n\n> | a | b |\n> |-|-|\n> | longer cell | d |";
+    /// let expected = "| a   | b   |\n| --- | --- |\n| c   | d   |\n\n> | a | b |\n> | - | - |\n> | longer cell | d |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Aligned`]: TableStyle::Aligned
+    /// [`Compact`]: TableStyle::Compact
+    /// [`AlignedUnlessTooWide`]: TableStyle::AlignedUnlessTooWide
+    pub fn table_style(&mut self, style: TableStyle) -> &mut Self {
+        self.config.set_table_style(style);
+        self
+    }
+
+    /// Configure whether the text in each table cell lines up with its column's alignment.
+    /// By default cells are always padded on the right.
+    ///
+    /// Cells are never padded in [`Compact`] tables.
+    ///
+    /// # Setting [`align_table_cells`](Self::align_table_cells) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.align_table_cells(false);
+    ///
+    /// let input = "| Left | Center | Right |\n|:-|:-:|-:|\n| a | b | c |";
+    /// let expected = "| Left | Center | Right |\n| :--- | :----: | ----: |\n| a    | b      | c     |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`align_table_cells`](Self::align_table_cells) to `true`
+    ///
+    /// Right aligned columns are padded on the left, and centered columns are padded on both
+    /// sides. Columns without an alignment are padded on the right.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.align_table_cells(true);
+    ///
+    /// let input = "| Left | Center | Right |\n|:-|:-:|-:|\n| a | b | c |";
+    /// let expected = "| Left | Center | Right |\n| :--- | :----: | ----: |\n| a    |   b    |     c |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Compact`]: TableStyle::Compact
+    pub fn align_table_cells(&mut self, align: bool) -> &mut Self {
+        self.config.set_align_table_cells(align);
+        self
+    }
+
+    /// Configure whether cells that are missing from the end of short table rows are written as
+    /// empty cells. By default missing cells only show up as padding in [`Aligned`] tables, and
+    /// short rows stay short in [`Compact`] tables.
+    ///
+    /// # Setting [`add_missing_table_cells`](Self::add_missing_table_cells) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, TableStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder
+    ///     .table_style(TableStyle::Compact)
+    ///     .add_missing_table_cells(false);
+    ///
+    /// let input = "| a | b | c |\n| - | - | - |\n| d |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`add_missing_table_cells`](Self::add_missing_table_cells) to `true`
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, TableStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder
+    ///     .table_style(TableStyle::Compact)
+    ///     .add_missing_table_cells(true);
+    ///
+    /// let input = "| a | b | c |\n| - | - | - |\n| d |";
+    /// let expected = "| a | b | c |\n| - | - | - |\n| d | | |";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Aligned`]: TableStyle::Aligned
+    /// [`Compact`]: TableStyle::Compact
+    pub fn add_missing_table_cells(&mut self, add: bool) -> &mut Self {
+        self.config.set_add_missing_table_cells(add);
+        self
+    }
+
+    /// Configure how the markers of nested block quotes are written.
+    ///
+    /// # Setting [`blockquote_style`](Self::blockquote_style) to [`Compact`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, BlockQuoteStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.blockquote_style(BlockQuoteStyle::Compact);
+    ///
+    /// let input = "> > nested\n> >\n> > quote";
+    /// let expected = ">> nested\n>>\n>> quote";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`blockquote_style`](Self::blockquote_style) to [`Spaced`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, BlockQuoteStyle};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.blockquote_style(BlockQuoteStyle::Spaced);
+    ///
+    /// let input = ">> nested\n>>\n>> quote";
+    /// let expected = "> > nested\n> >\n> > quote";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Compact`]: BlockQuoteStyle::Compact
+    /// [`Spaced`]: BlockQuoteStyle::Spaced
+    pub fn blockquote_style(&mut self, style: BlockQuoteStyle) -> &mut Self {
+        self.config.set_blockquote_style(style);
+        self
+    }
+
+    /// Configure how the content of list items is indented.
+    ///
+    /// Empty list items and list items that start with an indented code block are always
+    /// indented by the width of the list marker.
+    ///
+    /// # Setting [`list_indent`](Self::list_indent) to [`MarkerWidth`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ListIndent};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.list_indent(ListIndent::MarkerWidth);
+    ///
+    /// let input = "-   a\n\n    - b\n\n1.  c\n\n    d";
+    /// let expected = "- a\n\n  - b\n\n1. c\n\n   d";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`list_indent`](Self::list_indent) to [`TwoSpaces`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ListIndent};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.list_indent(ListIndent::TwoSpaces);
+    ///
+    /// let input = "-   a\n\n    - b\n\n1.  c\n\n    d";
+    /// let expected = "- a\n\n  - b\n\n1.  c\n\n    d";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`list_indent`](Self::list_indent) to [`FourSpaces`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ListIndent};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.list_indent(ListIndent::FourSpaces);
+    ///
+    /// let input = "- a\n\n  - b\n\n1. c\n\n   d";
+    /// let expected = "-   a\n\n    -   b\n\n1.  c\n\n    d";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`MarkerWidth`]: ListIndent::MarkerWidth
+    /// [`TwoSpaces`]: ListIndent::TwoSpaces
+    /// [`FourSpaces`]: ListIndent::FourSpaces
+    pub fn list_indent(&mut self, indent: ListIndent) -> &mut Self {
+        self.config.set_list_indent(indent);
+        self
+    }
+
+    /// Configure whether list items are separated by blank lines.
+    ///
+    /// Tight lists aren't rendered the same as loose lists. The items of loose lists are wrapped
+    /// in paragraphs, which usually adds space between them.
+    ///
+    /// # Setting [`list_spacing`](Self::list_spacing) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ListSpacing};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.list_spacing(ListSpacing::Preserve);
+    ///
+    /// let input = "- a\n- b\n\n- c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`list_spacing`](Self::list_spacing) to [`Tight`]
+    ///
+    /// Lists that contain list items with a blank line between blocks, like two paragraphs, are
+    /// loose no matter how their items are separated. Those lists get a blank line between every
+    /// item instead.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ListSpacing};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.list_spacing(ListSpacing::Tight);
+    ///
+    /// let input = "- a\n- b\n\n- c\n\n1. d\n\n   e\n2. f";
+    /// let expected = "- a\n- b\n- c\n\n1. d\n\n   e\n\n2. f";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`list_spacing`](Self::list_spacing) to [`Loose`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, ListSpacing};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.list_spacing(ListSpacing::Loose);
+    ///
+    /// let input = "- a\n- b\n\n- c";
+    /// let expected = "- a\n\n- b\n\n- c";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: ListSpacing::Preserve
+    /// [`Tight`]: ListSpacing::Tight
+    /// [`Loose`]: ListSpacing::Loose
+    pub fn list_spacing(&mut self, spacing: ListSpacing) -> &mut Self {
+        self.config.set_list_spacing(spacing);
+        self
+    }
+
+    /// Configure whether tabs in the leading whitespace of a line are replaced with spaces.
+    /// Tabs are expanded to the next multiple of 4 columns, which is where CommonMark puts its
+    /// tab stops.
+    ///
+    /// Code blocks and code spans always keep the tabs in their content.
+    ///
+    /// # Setting [`expand_leading_tabs`](Self::expand_leading_tabs) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.expand_leading_tabs(false);
+    ///
+    /// let input = "<div>\n\t<p>a</p>\n</div>\n\n```\n\tb\n```";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`expand_leading_tabs`](Self::expand_leading_tabs) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.expand_leading_tabs(true);
+    ///
+    /// let input = "<div>\n\t<p>a</p>\n</div>\n\n```\n\tb\n```";
+    /// let expected = "<div>\n    <p>a</p>\n</div>\n\n```\n\tb\n```";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn expand_leading_tabs(&mut self, expand: bool) -> &mut Self {
+        self.config.set_expand_leading_tabs(expand);
+        self
+    }
+
+    /// Configure the line ending that's written at the end of each line.
+    ///
+    /// Every line ending in the output is replaced, including the ones in code blocks and HTML.
+    ///
+    /// # Setting [`line_ending`](Self::line_ending) to [`Lf`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LineEnding};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.line_ending(LineEnding::Lf);
+    ///
+    /// let input = "# Title\r\n\r\n```\r\ncode\r\n```\r\n";
+    /// let expected = "# Title\n\n```\ncode\n```\n";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`line_ending`](Self::line_ending) to [`CrLf`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LineEnding};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.line_ending(LineEnding::CrLf);
+    ///
+    /// let input = "# Title\n\n```\ncode\n```\n";
+    /// let expected = "# Title\r\n\r\n```\r\ncode\r\n```\r\n";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`line_ending`](Self::line_ending) to [`Preserve`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, LineEnding};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.line_ending(LineEnding::Preserve);
+    ///
+    /// let input = "# Title\r\n\r\nSome text\nthat was edited\r\n";
+    /// let expected = "# Title\r\n\r\nSome text\r\nthat was edited\r\n";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Lf`]: LineEnding::Lf
+    /// [`CrLf`]: LineEnding::CrLf
+    /// [`Preserve`]: LineEnding::Preserve
+    pub fn line_ending(&mut self, line_ending: LineEnding) -> &mut Self {
+        self.config.set_line_ending(line_ending);
+        self
+    }
+
+    /// Configure whether the output always ends with a single newline. By default the output
+    /// only ends with a newline when the input does.
+    ///
+    /// Empty output is never given a newline.
+    ///
+    /// # Setting [`insert_final_newline`](Self::insert_final_newline) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.insert_final_newline(false);
+    /// let formatter = builder.build();
+    ///
+    /// let output = formatter.format("# Title").unwrap();
+    /// assert_eq!(output, "# Title");
+    ///
+    /// let output = formatter.format("# Title\n\n\n").unwrap();
+    /// assert_eq!(output, "# Title\n");
+    /// ```
+    /// ---
+    /// # Setting [`insert_final_newline`](Self::insert_final_newline) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.insert_final_newline(true);
+    /// let formatter = builder.build();
+    ///
+    /// let output = formatter.format("# Title").unwrap();
+    /// assert_eq!(output, "# Title\n");
+    ///
+    /// let output = formatter.format("# Title\n\n\n").unwrap();
+    /// assert_eq!(output, "# Title\n");
+    /// ```
+    pub fn insert_final_newline(&mut self, insert: bool) -> &mut Self {
+        self.config.set_insert_final_newline(insert);
+        self
+    }
+
+    /// Configure whether a UTF-8 byte order mark at the start of the input is removed. By default
+    /// it's written at the start of the output.
+    ///
+    /// The byte order mark is never treated as part of the Markdown, so it doesn't stop a heading
+    /// on the first line from being a heading.
+    ///
+    /// # Setting [`remove_byte_order_mark`](Self::remove_byte_order_mark) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_byte_order_mark(false);
+    ///
+    /// let input = "\u{feff}#  Title";
+    /// let expected = "\u{feff}# Title";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`remove_byte_order_mark`](Self::remove_byte_order_mark) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_byte_order_mark(true);
+    ///
+    /// let input = "\u{feff}#  Title";
+    /// let expected = "# Title";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn remove_byte_order_mark(&mut self, remove: bool) -> &mut Self {
+        self.config.set_remove_byte_order_mark(remove);
+        self
+    }
+
+    /// Configure the maximum number of blank lines in a row. By default all the blank lines
+    /// between blocks are kept.
+    ///
+    /// At least one blank line is always kept wherever the source had one, since blocks like
+    /// paragraphs would be merged without it. Blank lines in code blocks and HTML blocks are part
+    /// of their content, so they're never removed.
+    ///
+    /// # Setting [`max_blank_lines`](Self::max_blank_lines) to `None` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.max_blank_lines(None);
+    ///
+    /// let input = "a\n\n\n\nb";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`max_blank_lines`](Self::max_blank_lines) to `Some(1)`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.max_blank_lines(Some(1));
+    ///
+    /// let input = "a\n\n\n\nb\n\n\n```\nc\n\n\nd\n```";
+    /// let expected = "a\n\nb\n\n```\nc\n\n\nd\n```";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn max_blank_lines(&mut self, max: Option<usize>) -> &mut Self {
+        self.config.set_max_blank_lines(max);
+        self
+    }
+
+    /// Configure whether headings, fenced code blocks, tables, and HTML blocks always have a
+    /// blank line between them and the blocks around them.
+    ///
+    /// Blank lines aren't added between the blocks of a tight list's items, since that would
+    /// make the list loose.
+    ///
+    /// # Setting [`blank_lines_around_blocks`](Self::blank_lines_around_blocks) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.blank_lines_around_blocks(false);
+    ///
+    /// let input = "# Heading\ntext\n```\ncode\n```\n- # item heading\n  item text";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`blank_lines_around_blocks`](Self::blank_lines_around_blocks) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.blank_lines_around_blocks(true);
+    ///
+    /// let input = "# Heading\ntext\n```\ncode\n```\n- # item heading\n  item text";
+    /// let expected = "# Heading\n\ntext\n\n```\ncode\n```\n\n- # item heading\n  item text";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn blank_lines_around_blocks(&mut self, blank_lines: bool) -> &mut Self {
+        self.config.set_blank_lines_around_blocks(blank_lines);
+        self
+    }
+
+    /// Configure whether blank lines at the start of block quotes are removed.
+    ///
+    /// List items can't start with a blank line, so block quotes are the only containers that
+    /// can.
+    ///
+    /// # Setting [`remove_leading_blank_lines`](Self::remove_leading_blank_lines) to `false` (default)
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_leading_blank_lines(false);
+    ///
+    /// let input = ">\n>\n> a\n\n- >\n  > b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`remove_leading_blank_lines`](Self::remove_leading_blank_lines) to `true`
+    /// ```rust
+    /// # use markdown_fmt::FormatBuilder;
+    /// let mut builder = FormatBuilder::default();
+    /// builder.remove_leading_blank_lines(true);
+    ///
+    /// let input = ">\n>\n> a\n\n- >\n  > b";
+    /// let expected = "> a\n\n- > b";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    pub fn remove_leading_blank_lines(&mut self, remove: bool) -> &mut Self {
+        self.config.set_remove_leading_blank_lines(remove);
+        self
+    }
+
+    /// Configure how hard line breaks are written.
+    ///
+    /// Two spaces at the end of a line are hard to see, and some editors remove them.
+    ///
+    /// # Setting [`hard_break`](Self::hard_break) to [`Preserve`] (default)
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HardBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.hard_break(HardBreak::Preserve);
+    ///
+    /// let input = "a\\\nb  \nc";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, input)
+    /// ```
+    /// ---
+    /// # Setting [`hard_break`](Self::hard_break) to [`Backslash`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HardBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.hard_break(HardBreak::Backslash);
+    ///
+    /// let input = "a\\\nb  \nc";
+    /// let expected = "a\\\nb\\\nc";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    /// ---
+    /// # Setting [`hard_break`](Self::hard_break) to [`TwoSpaces`]
+    /// ```rust
+    /// # use markdown_fmt::{FormatBuilder, HardBreak};
+    /// let mut builder = FormatBuilder::default();
+    /// builder.hard_break(HardBreak::TwoSpaces);
+    ///
+    /// let input = "a\\\nb  \nc";
+    /// let expected = "a  \nb  \nc";
+    /// let output = builder.build().format(input).unwrap();
+    /// assert_eq!(output, expected)
+    /// ```
+    ///
+    /// [`Preserve`]: HardBreak::Preserve
+    /// [`Backslash`]: HardBreak::Backslash
+    /// [`TwoSpaces`]: HardBreak::TwoSpaces
+    pub fn hard_break(&mut self, style: HardBreak) -> &mut Self {
+        self.config.set_hard_break(style);
+        self
+    }
+
     /// Internal setter for Config. Used for testing
     #[cfg(test)]
     pub(crate) fn config(&mut self, config: Config) -> &mut Self {
@@ -301,4 +1895,80 @@ mod test {
             .unwrap();
         assert_eq!(CHECK_FORMATTING_CONTEXT_INPUT, output)
     }
+
+    #[test]
+    fn fenced_indented_code_blocks_use_code_block_formatter() {
+        let mut builder = FormatBuilder::with_code_block_formatter(|_, info_str, code_block| {
+            assert_eq!(info_str, "text");
+            code_block.to_uppercase()
+        });
+        builder.fence_indented_code_blocks(Some("text"));
+
+        let output = builder.build().format("    indented code").unwrap();
+        assert_eq!(output, "```text\nINDENTED CODE\n```")
+    }
+
+    #[test]
+    fn check_formatting_context_with_backtick_code_fences() {
+        let mut builder = FormatBuilder::default();
+        builder.code_fence_style(CodeFenceStyle::Backtick);
+
+        let expected = "# test
+
+```level_0
+```
+
+* ```level_1
+  ```
+
+* ````markdown
+  * ```level_2
+    ```
+  + >>>>> ```level_3
+    >>>>> ```
+  ````
+
+````markdown
+```level_4
+```
+````
+";
+        let output = builder
+            .build()
+            .format(CHECK_FORMATTING_CONTEXT_INPUT)
+            .unwrap();
+        assert_eq!(expected, output)
+    }
+
+    #[test]
+    fn check_formatting_context_with_tilde_code_fences() {
+        let mut builder = FormatBuilder::default();
+        builder.code_fence_style(CodeFenceStyle::Tilde);
+
+        let expected = "# test
+
+~~~level_0
+~~~
+
+* ~~~level_1
+  ~~~
+
+* ~~~~markdown
+  * ~~~level_2
+    ~~~
+  + >>>>> ~~~level_3
+    >>>>> ~~~
+  ~~~~
+
+~~~~markdown
+~~~level_4
+~~~
+~~~~
+";
+        let output = builder
+            .build()
+            .format(CHECK_FORMATTING_CONTEXT_INPUT)
+            .unwrap();
+        assert_eq!(expected, output)
+    }
 }
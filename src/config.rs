@@ -1,7 +1,322 @@
+/// How ordered list items should be numbered when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrderedListNumbering {
+    /// Count up from the list's start number. For example, `1.`, `2.`, `3.`.
+    Sequential,
+    /// Write `1.` for every list item.
+    ///
+    /// Lists that start at a number other than `1` repeat their start number instead,
+    /// so that the rendered list still starts at the same number.
+    One,
+    /// Keep the number that each list item had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// The delimiter written after the number of an ordered list item.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrderedListDelimiter {
+    /// `.` For example, `1.`
+    Period,
+    /// `)` For example, `1)`
+    Parenthesis,
+    /// Keep the delimiter that each list had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// A character that can be used as an unordered list marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bullet {
+    /// `-`
+    Hyphen,
+    /// `*`
+    Asterisk,
+    /// `+`
+    Plus,
+}
+
+/// How unordered list markers should be written when formatting.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum BulletStyle {
+    /// Use the same [Bullet] for every unordered list.
+    Consistent(Bullet),
+    /// Cycle through the given [Bullet]s based on how deeply the unordered list is nested.
+    ///
+    /// For example, `vec![Bullet::Hyphen, Bullet::Asterisk]` uses `-` for top level lists,
+    /// `*` for lists nested one level deep, `-` for lists nested two levels deep, and so on.
+    /// An empty `Vec` behaves like [BulletStyle::Preserve].
+    AlternateByDepth(Vec<Bullet>),
+    /// Keep the marker that each list had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// How list item content should be indented when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListIndent {
+    /// Indent content by the width of the list marker (default). For example, two spaces for
+    /// `-` and three spaces for `1.`.
+    #[default]
+    MarkerWidth,
+    /// Indent content by a multiple of two spaces, padding the space after the list marker so
+    /// the content lines up. For example, `-` is followed by one space and `1.` by two spaces.
+    TwoSpaces,
+    /// Indent content by a multiple of four spaces, padding the space after the list marker so
+    /// the content lines up. For example, `-   item`.
+    FourSpaces,
+}
+
+/// How list items should be separated when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListSpacing {
+    /// Don't write blank lines between list items.
+    ///
+    /// Lists where a list item contains blocks that are separated by a blank line are always
+    /// loose, so those lists are written with a blank line between every item instead.
+    Tight,
+    /// Write a blank line between every list item.
+    Loose,
+    /// Keep the blank lines between list items from the source (default).
+    #[default]
+    Preserve,
+}
+
+/// The delimiter used for emphasis and strong emphasis.
+///
+/// Strong emphasis uses two of the delimiter characters. For example, `**` or `__`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmphasisMarker {
+    /// `*` For example, `*emphasis*` or `**strong**`.
+    Asterisk,
+    /// `_` For example, `_emphasis_` or `__strong__`.
+    Underscore,
+    /// Keep the delimiter that was used in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// The delimiter used for strikethrough.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StrikethroughMarker {
+    /// `~` For example, `~strikethrough~`.
+    SingleTilde,
+    /// `~~` For example, `~~strikethrough~~`.
+    DoubleTilde,
+    /// Keep the delimiter that was used in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// How headings should be written when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HeadingStyle {
+    /// Write every heading with leading `#` characters. For example, `# Heading`.
+    ///
+    /// Setext headings that span multiple lines are kept as setext headings,
+    /// since ATX headings can't span multiple lines.
+    Atx,
+    /// Underline level 1 and level 2 headings with `=` or `-`. For example, `Heading\n===`.
+    ///
+    /// Levels 3 through 6 don't have a setext form and are written as ATX headings.
+    Setext,
+    /// Keep the style that each heading had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// The character used for the fences of fenced code blocks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CodeFenceStyle {
+    /// Fence code blocks with backticks. For example, ```` ``` ````.
+    ///
+    /// Code blocks with an info string that contains a backtick are fenced with tildes instead.
+    Backtick,
+    /// Fence code blocks with tildes. For example, `~~~`.
+    Tilde,
+    /// Keep the code fence that each code block had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// How thematic breaks should be written when formatting.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum ThematicBreak {
+    /// Write every thematic break as the given string. For example, `"---"` or `"* * *"`.
+    ///
+    /// Strings that aren't valid thematic breaks are ignored, and the source text is kept.
+    Canonical(String),
+    /// Repeat the given character (`-`, `*`, or `_`) to fill the configured `max_width`.
+    ///
+    /// Three characters are written when `max_width` isn't configured.
+    FillWidth(char),
+    /// Keep the text that each thematic break had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// How hard line breaks should be written when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HardBreak {
+    /// A `\` at the end of the line.
+    Backslash,
+    /// Two spaces at the end of the line.
+    TwoSpaces,
+    /// Keep the style that each hard break had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// Where link reference definitions should be written when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinkDefinitionPlacement {
+    /// Move every definition to the end of the document.
+    EndOfDocument,
+    /// Move every definition to the end of the section it was defined in.
+    ///
+    /// Sections end right before the next heading that isn't nested in a block quote,
+    /// list, or footnote definition.
+    EndOfSection,
+    /// Keep each definition where it was in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// Where footnote definitions should be written when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FootnoteDefinitionPlacement {
+    /// Move every definition to the end of the document, in the order that the footnotes are
+    /// first referenced. Definitions that are never referenced come last.
+    EndOfDocument,
+    /// Keep each definition where it was in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// How the content of footnote definitions should be indented when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FootnoteIndentation {
+    /// Indent content by two spaces.
+    ///
+    /// Only footnote definitions that contain a single paragraph can be indented by two spaces.
+    /// The paragraph starts on the same line as the label. Footnote definitions with more than
+    /// one block keep four-space indentation.
+    TwoSpaces,
+    /// Indent content by four spaces (default).
+    #[default]
+    FourSpaces,
+}
+
+/// How tables should be laid out when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyle {
+    /// Pad every cell to the width of the widest cell in its column (default).
+    #[default]
+    Aligned,
+    /// Don't pad cells, and write the shortest delimiter row. For example, `| a | b |` and
+    /// `| - | :-: |`.
+    Compact,
+    /// Write [TableStyle::Aligned] tables unless they'd be wider than the configured
+    /// `max_width`, and [TableStyle::Compact] tables otherwise.
+    ///
+    /// Tables are always aligned when `max_width` isn't configured.
+    AlignedUnlessTooWide,
+}
+
+/// How the markers of nested block quotes should be written when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BlockQuoteStyle {
+    /// Write nested block quote markers next to each other (default). For example, `>> quote`.
+    #[default]
+    Compact,
+    /// Separate nested block quote markers with a space. For example, `> > quote`.
+    Spaced,
+}
+
+/// The quotes used to wrap link reference definition titles.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinkTitleStyle {
+    /// Wrap titles in double quotes. For example, `[label]: /url "title"`.
+    DoubleQuote,
+    /// Wrap titles in single quotes. For example, `[label]: /url 'title'`.
+    SingleQuote,
+    /// Wrap titles in parentheses. For example, `[label]: /url (title)`.
+    Parentheses,
+    /// Keep the quotes that each title had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// How links and images should be written when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStyle {
+    /// Write links with their destination and title inline. For example, `[text](/url "title")`.
+    ///
+    /// Link reference definitions that are no longer used by any link are removed.
+    Inline,
+    /// Write links as full reference links. For example, `[text][label]`.
+    ///
+    /// Link reference definitions are added for inline links.
+    Reference,
+    /// Keep the style that each link had in the source (default).
+    #[default]
+    Preserve,
+}
+
+/// The line ending written at the end of each line when formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n` (default)
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// Use the line ending that's most common in the source for every line. `\n` is used when
+    /// the source has as many `\n` line endings as `\r\n` line endings.
+    Preserve,
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct Config {
     max_width: Option<usize>,
     reflow_text: bool,
+    ordered_list_numbering: OrderedListNumbering,
+    ordered_list_delimiter: OrderedListDelimiter,
+    bullet_style: BulletStyle,
+    emphasis_marker: EmphasisMarker,
+    strong_marker: EmphasisMarker,
+    strikethrough_marker: StrikethroughMarker,
+    heading_style: HeadingStyle,
+    close_atx_headings: bool,
+    code_fence_style: CodeFenceStyle,
+    fence_indented_code_blocks: Option<String>,
+    thematic_break: ThematicBreak,
+    link_definition_placement: LinkDefinitionPlacement,
+    sort_link_definitions: bool,
+    deduplicate_link_definitions: bool,
+    normalize_link_labels: bool,
+    link_title_style: LinkTitleStyle,
+    link_style: LinkStyle,
+    remove_unused_link_definitions: bool,
+    report_undefined_shortcut_references: bool,
+    footnote_definition_placement: FootnoteDefinitionPlacement,
+    renumber_footnotes: bool,
+    remove_unused_footnote_definitions: bool,
+    footnote_indentation: FootnoteIndentation,
+    table_style: TableStyle,
+    align_table_cells: bool,
+    add_missing_table_cells: bool,
+    blockquote_style: BlockQuoteStyle,
+    list_indent: ListIndent,
+    list_spacing: ListSpacing,
+    expand_leading_tabs: bool,
+    line_ending: LineEnding,
+    insert_final_newline: bool,
+    remove_byte_order_mark: bool,
+    max_blank_lines: Option<usize>,
+    blank_lines_around_blocks: bool,
+    remove_leading_blank_lines: bool,
+    hard_break: HardBreak,
 }
 
 impl Config {
@@ -21,6 +336,302 @@ impl Config {
         self.reflow_text = value;
     }
 
+    pub(crate) fn ordered_list_numbering(&self) -> OrderedListNumbering {
+        self.ordered_list_numbering
+    }
+
+    pub(crate) fn set_ordered_list_numbering(&mut self, value: OrderedListNumbering) {
+        self.ordered_list_numbering = value;
+    }
+
+    pub(crate) fn ordered_list_delimiter(&self) -> OrderedListDelimiter {
+        self.ordered_list_delimiter
+    }
+
+    pub(crate) fn set_ordered_list_delimiter(&mut self, value: OrderedListDelimiter) {
+        self.ordered_list_delimiter = value;
+    }
+
+    pub(crate) fn bullet_style(&self) -> &BulletStyle {
+        &self.bullet_style
+    }
+
+    pub(crate) fn set_bullet_style(&mut self, value: BulletStyle) {
+        self.bullet_style = value;
+    }
+
+    pub(crate) fn emphasis_marker(&self) -> EmphasisMarker {
+        self.emphasis_marker
+    }
+
+    pub(crate) fn set_emphasis_marker(&mut self, value: EmphasisMarker) {
+        self.emphasis_marker = value;
+    }
+
+    pub(crate) fn strong_marker(&self) -> EmphasisMarker {
+        self.strong_marker
+    }
+
+    pub(crate) fn set_strong_marker(&mut self, value: EmphasisMarker) {
+        self.strong_marker = value;
+    }
+
+    pub(crate) fn strikethrough_marker(&self) -> StrikethroughMarker {
+        self.strikethrough_marker
+    }
+
+    pub(crate) fn set_strikethrough_marker(&mut self, value: StrikethroughMarker) {
+        self.strikethrough_marker = value;
+    }
+
+    pub(crate) fn heading_style(&self) -> HeadingStyle {
+        self.heading_style
+    }
+
+    pub(crate) fn set_heading_style(&mut self, value: HeadingStyle) {
+        self.heading_style = value;
+    }
+
+    pub(crate) fn close_atx_headings(&self) -> bool {
+        self.close_atx_headings
+    }
+
+    pub(crate) fn set_close_atx_headings(&mut self, value: bool) {
+        self.close_atx_headings = value;
+    }
+
+    pub(crate) fn code_fence_style(&self) -> CodeFenceStyle {
+        self.code_fence_style
+    }
+
+    pub(crate) fn set_code_fence_style(&mut self, value: CodeFenceStyle) {
+        self.code_fence_style = value;
+    }
+
+    pub(crate) fn fence_indented_code_blocks(&self) -> Option<&str> {
+        self.fence_indented_code_blocks.as_deref()
+    }
+
+    pub(crate) fn set_fence_indented_code_blocks(&mut self, value: Option<String>) {
+        self.fence_indented_code_blocks = value;
+    }
+
+    pub(crate) fn thematic_break(&self) -> &ThematicBreak {
+        &self.thematic_break
+    }
+
+    pub(crate) fn set_thematic_break(&mut self, value: ThematicBreak) {
+        self.thematic_break = value;
+    }
+
+    pub(crate) fn link_definition_placement(&self) -> LinkDefinitionPlacement {
+        self.link_definition_placement
+    }
+
+    pub(crate) fn set_link_definition_placement(&mut self, value: LinkDefinitionPlacement) {
+        self.link_definition_placement = value;
+    }
+
+    pub(crate) fn sort_link_definitions(&self) -> bool {
+        self.sort_link_definitions
+    }
+
+    pub(crate) fn set_sort_link_definitions(&mut self, value: bool) {
+        self.sort_link_definitions = value;
+    }
+
+    pub(crate) fn deduplicate_link_definitions(&self) -> bool {
+        self.deduplicate_link_definitions
+    }
+
+    pub(crate) fn set_deduplicate_link_definitions(&mut self, value: bool) {
+        self.deduplicate_link_definitions = value;
+    }
+
+    pub(crate) fn normalize_link_labels(&self) -> bool {
+        self.normalize_link_labels
+    }
+
+    pub(crate) fn set_normalize_link_labels(&mut self, value: bool) {
+        self.normalize_link_labels = value;
+    }
+
+    pub(crate) fn link_title_style(&self) -> LinkTitleStyle {
+        self.link_title_style
+    }
+
+    pub(crate) fn set_link_title_style(&mut self, value: LinkTitleStyle) {
+        self.link_title_style = value;
+    }
+
+    pub(crate) fn link_style(&self) -> LinkStyle {
+        self.link_style
+    }
+
+    pub(crate) fn set_link_style(&mut self, value: LinkStyle) {
+        self.link_style = value;
+    }
+
+    pub(crate) fn remove_unused_link_definitions(&self) -> bool {
+        self.remove_unused_link_definitions
+    }
+
+    pub(crate) fn set_remove_unused_link_definitions(&mut self, value: bool) {
+        self.remove_unused_link_definitions = value;
+    }
+
+    pub(crate) fn report_undefined_shortcut_references(&self) -> bool {
+        self.report_undefined_shortcut_references
+    }
+
+    pub(crate) fn set_report_undefined_shortcut_references(&mut self, value: bool) {
+        self.report_undefined_shortcut_references = value;
+    }
+
+    pub(crate) fn footnote_definition_placement(&self) -> FootnoteDefinitionPlacement {
+        self.footnote_definition_placement
+    }
+
+    pub(crate) fn set_footnote_definition_placement(&mut self, value: FootnoteDefinitionPlacement) {
+        self.footnote_definition_placement = value;
+    }
+
+    pub(crate) fn renumber_footnotes(&self) -> bool {
+        self.renumber_footnotes
+    }
+
+    pub(crate) fn set_renumber_footnotes(&mut self, value: bool) {
+        self.renumber_footnotes = value;
+    }
+
+    pub(crate) fn remove_unused_footnote_definitions(&self) -> bool {
+        self.remove_unused_footnote_definitions
+    }
+
+    pub(crate) fn set_remove_unused_footnote_definitions(&mut self, value: bool) {
+        self.remove_unused_footnote_definitions = value;
+    }
+
+    pub(crate) fn footnote_indentation(&self) -> FootnoteIndentation {
+        self.footnote_indentation
+    }
+
+    pub(crate) fn set_footnote_indentation(&mut self, value: FootnoteIndentation) {
+        self.footnote_indentation = value;
+    }
+
+    pub(crate) fn table_style(&self) -> TableStyle {
+        self.table_style
+    }
+
+    pub(crate) fn set_table_style(&mut self, value: TableStyle) {
+        self.table_style = value;
+    }
+
+    pub(crate) fn align_table_cells(&self) -> bool {
+        self.align_table_cells
+    }
+
+    pub(crate) fn set_align_table_cells(&mut self, value: bool) {
+        self.align_table_cells = value;
+    }
+
+    pub(crate) fn add_missing_table_cells(&self) -> bool {
+        self.add_missing_table_cells
+    }
+
+    pub(crate) fn set_add_missing_table_cells(&mut self, value: bool) {
+        self.add_missing_table_cells = value;
+    }
+
+    pub(crate) fn blockquote_style(&self) -> BlockQuoteStyle {
+        self.blockquote_style
+    }
+
+    pub(crate) fn set_blockquote_style(&mut self, value: BlockQuoteStyle) {
+        self.blockquote_style = value;
+    }
+
+    pub(crate) fn list_indent(&self) -> ListIndent {
+        self.list_indent
+    }
+
+    pub(crate) fn set_list_indent(&mut self, value: ListIndent) {
+        self.list_indent = value;
+    }
+
+    pub(crate) fn list_spacing(&self) -> ListSpacing {
+        self.list_spacing
+    }
+
+    pub(crate) fn set_list_spacing(&mut self, value: ListSpacing) {
+        self.list_spacing = value;
+    }
+
+    pub(crate) fn expand_leading_tabs(&self) -> bool {
+        self.expand_leading_tabs
+    }
+
+    pub(crate) fn set_expand_leading_tabs(&mut self, value: bool) {
+        self.expand_leading_tabs = value;
+    }
+
+    pub(crate) fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    pub(crate) fn set_line_ending(&mut self, value: LineEnding) {
+        self.line_ending = value;
+    }
+
+    pub(crate) fn insert_final_newline(&self) -> bool {
+        self.insert_final_newline
+    }
+
+    pub(crate) fn set_insert_final_newline(&mut self, value: bool) {
+        self.insert_final_newline = value;
+    }
+
+    pub(crate) fn remove_byte_order_mark(&self) -> bool {
+        self.remove_byte_order_mark
+    }
+
+    pub(crate) fn set_remove_byte_order_mark(&mut self, value: bool) {
+        self.remove_byte_order_mark = value;
+    }
+
+    pub(crate) fn max_blank_lines(&self) -> Option<usize> {
+        self.max_blank_lines
+    }
+
+    pub(crate) fn set_max_blank_lines(&mut self, value: Option<usize>) {
+        self.max_blank_lines = value;
+    }
+
+    pub(crate) fn blank_lines_around_blocks(&self) -> bool {
+        self.blank_lines_around_blocks
+    }
+
+    pub(crate) fn set_blank_lines_around_blocks(&mut self, value: bool) {
+        self.blank_lines_around_blocks = value;
+    }
+
+    pub(crate) fn remove_leading_blank_lines(&self) -> bool {
+        self.remove_leading_blank_lines
+    }
+
+    pub(crate) fn set_remove_leading_blank_lines(&mut self, value: bool) {
+        self.remove_leading_blank_lines = value;
+    }
+
+    pub(crate) fn hard_break(&self) -> HardBreak {
+        self.hard_break
+    }
+
+    pub(crate) fn set_hard_break(&mut self, value: HardBreak) {
+        self.hard_break = value;
+    }
+
     /// Internal setter for config options. Used for testing
     #[cfg(test)]
     pub(crate) fn set(&mut self, field: &str, value: &str) {
@@ -33,6 +644,237 @@ impl Config {
                 let value = value.parse::<bool>().unwrap();
                 self.reflow_text = value;
             }
+            "ordered_list_numbering" => {
+                self.ordered_list_numbering = match value {
+                    "sequential" => OrderedListNumbering::Sequential,
+                    "one" => OrderedListNumbering::One,
+                    "preserve" => OrderedListNumbering::Preserve,
+                    _ => panic!("unknown ordered_list_numbering value {value}"),
+                };
+            }
+            "ordered_list_delimiter" => {
+                self.ordered_list_delimiter = match value {
+                    "." => OrderedListDelimiter::Period,
+                    ")" => OrderedListDelimiter::Parenthesis,
+                    "preserve" => OrderedListDelimiter::Preserve,
+                    _ => panic!("unknown ordered_list_delimiter value {value}"),
+                };
+            }
+            "bullet_style" => {
+                let parse_bullet = |bullet: &str| match bullet.trim() {
+                    "-" => Bullet::Hyphen,
+                    "*" => Bullet::Asterisk,
+                    "+" => Bullet::Plus,
+                    _ => panic!("unknown bullet {bullet}"),
+                };
+                self.bullet_style = match value {
+                    "preserve" => BulletStyle::Preserve,
+                    _ if value.contains(',') => {
+                        BulletStyle::AlternateByDepth(value.split(',').map(parse_bullet).collect())
+                    }
+                    _ => BulletStyle::Consistent(parse_bullet(value)),
+                };
+            }
+            "emphasis_marker" => {
+                self.emphasis_marker = match value {
+                    "*" => EmphasisMarker::Asterisk,
+                    "_" => EmphasisMarker::Underscore,
+                    "preserve" => EmphasisMarker::Preserve,
+                    _ => panic!("unknown emphasis_marker value {value}"),
+                };
+            }
+            "strong_marker" => {
+                self.strong_marker = match value {
+                    "**" => EmphasisMarker::Asterisk,
+                    "__" => EmphasisMarker::Underscore,
+                    "preserve" => EmphasisMarker::Preserve,
+                    _ => panic!("unknown strong_marker value {value}"),
+                };
+            }
+            "strikethrough_marker" => {
+                self.strikethrough_marker = match value {
+                    "~" => StrikethroughMarker::SingleTilde,
+                    "~~" => StrikethroughMarker::DoubleTilde,
+                    "preserve" => StrikethroughMarker::Preserve,
+                    _ => panic!("unknown strikethrough_marker value {value}"),
+                };
+            }
+            "heading_style" => {
+                self.heading_style = match value {
+                    "atx" => HeadingStyle::Atx,
+                    "setext" => HeadingStyle::Setext,
+                    "preserve" => HeadingStyle::Preserve,
+                    _ => panic!("unknown heading_style value {value}"),
+                };
+            }
+            "close_atx_headings" => {
+                let value = value.parse::<bool>().unwrap();
+                self.close_atx_headings = value;
+            }
+            "code_fence_style" => {
+                self.code_fence_style = match value {
+                    "`" => CodeFenceStyle::Backtick,
+                    "~" => CodeFenceStyle::Tilde,
+                    "preserve" => CodeFenceStyle::Preserve,
+                    _ => panic!("unknown code_fence_style value {value}"),
+                };
+            }
+            "fence_indented_code_blocks" => {
+                self.fence_indented_code_blocks = Some(value.to_owned());
+            }
+            "thematic_break" => {
+                self.thematic_break = match value.split_once(':') {
+                    _ if value == "preserve" => ThematicBreak::Preserve,
+                    Some(("fill", c)) => ThematicBreak::FillWidth(c.parse::<char>().unwrap()),
+                    _ => ThematicBreak::Canonical(value.to_owned()),
+                };
+            }
+            "link_definition_placement" => {
+                self.link_definition_placement = match value {
+                    "end_of_document" => LinkDefinitionPlacement::EndOfDocument,
+                    "end_of_section" => LinkDefinitionPlacement::EndOfSection,
+                    "preserve" => LinkDefinitionPlacement::Preserve,
+                    _ => panic!("unknown link_definition_placement value {value}"),
+                };
+            }
+            "sort_link_definitions" => {
+                let value = value.parse::<bool>().unwrap();
+                self.sort_link_definitions = value;
+            }
+            "deduplicate_link_definitions" => {
+                let value = value.parse::<bool>().unwrap();
+                self.deduplicate_link_definitions = value;
+            }
+            "normalize_link_labels" => {
+                let value = value.parse::<bool>().unwrap();
+                self.normalize_link_labels = value;
+            }
+            "link_title_style" => {
+                self.link_title_style = match value {
+                    "\"" => LinkTitleStyle::DoubleQuote,
+                    "'" => LinkTitleStyle::SingleQuote,
+                    "()" => LinkTitleStyle::Parentheses,
+                    "preserve" => LinkTitleStyle::Preserve,
+                    _ => panic!("unknown link_title_style value {value}"),
+                };
+            }
+            "link_style" => {
+                self.link_style = match value {
+                    "inline" => LinkStyle::Inline,
+                    "reference" => LinkStyle::Reference,
+                    "preserve" => LinkStyle::Preserve,
+                    _ => panic!("unknown link_style value {value}"),
+                };
+            }
+            "remove_unused_link_definitions" => {
+                let value = value.parse::<bool>().unwrap();
+                self.remove_unused_link_definitions = value;
+            }
+            "report_undefined_shortcut_references" => {
+                let value = value.parse::<bool>().unwrap();
+                self.report_undefined_shortcut_references = value;
+            }
+            "footnote_definition_placement" => {
+                self.footnote_definition_placement = match value {
+                    "end_of_document" => FootnoteDefinitionPlacement::EndOfDocument,
+                    "preserve" => FootnoteDefinitionPlacement::Preserve,
+                    _ => panic!("unknown footnote_definition_placement value {value}"),
+                };
+            }
+            "renumber_footnotes" => {
+                let value = value.parse::<bool>().unwrap();
+                self.renumber_footnotes = value;
+            }
+            "remove_unused_footnote_definitions" => {
+                let value = value.parse::<bool>().unwrap();
+                self.remove_unused_footnote_definitions = value;
+            }
+            "footnote_indentation" => {
+                self.footnote_indentation = match value {
+                    "two_spaces" => FootnoteIndentation::TwoSpaces,
+                    "four_spaces" => FootnoteIndentation::FourSpaces,
+                    _ => panic!("unknown footnote_indentation value {value}"),
+                };
+            }
+            "table_style" => {
+                self.table_style = match value {
+                    "aligned" => TableStyle::Aligned,
+                    "compact" => TableStyle::Compact,
+                    "aligned_unless_too_wide" => TableStyle::AlignedUnlessTooWide,
+                    _ => panic!("unknown table_style value {value}"),
+                };
+            }
+            "align_table_cells" => {
+                let value = value.parse::<bool>().unwrap();
+                self.align_table_cells = value;
+            }
+            "add_missing_table_cells" => {
+                let value = value.parse::<bool>().unwrap();
+                self.add_missing_table_cells = value;
+            }
+            "blockquote_style" => {
+                self.blockquote_style = match value {
+                    "compact" => BlockQuoteStyle::Compact,
+                    "spaced" => BlockQuoteStyle::Spaced,
+                    _ => panic!("unknown blockquote_style value {value}"),
+                };
+            }
+            "list_indent" => {
+                self.list_indent = match value {
+                    "marker_width" => ListIndent::MarkerWidth,
+                    "two_spaces" => ListIndent::TwoSpaces,
+                    "four_spaces" => ListIndent::FourSpaces,
+                    _ => panic!("unknown list_indent value {value}"),
+                };
+            }
+            "list_spacing" => {
+                self.list_spacing = match value {
+                    "tight" => ListSpacing::Tight,
+                    "loose" => ListSpacing::Loose,
+                    "preserve" => ListSpacing::Preserve,
+                    _ => panic!("unknown list_spacing value {value}"),
+                };
+            }
+            "expand_leading_tabs" => {
+                let value = value.parse::<bool>().unwrap();
+                self.expand_leading_tabs = value;
+            }
+            "line_ending" => {
+                self.line_ending = match value {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::CrLf,
+                    "preserve" => LineEnding::Preserve,
+                    _ => panic!("unknown line_ending value {value}"),
+                };
+            }
+            "insert_final_newline" => {
+                let value = value.parse::<bool>().unwrap();
+                self.insert_final_newline = value;
+            }
+            "remove_byte_order_mark" => {
+                let value = value.parse::<bool>().unwrap();
+                self.remove_byte_order_mark = value;
+            }
+            "max_blank_lines" => {
+                let value = value.parse::<usize>().unwrap();
+                self.max_blank_lines = Some(value)
+            }
+            "blank_lines_around_blocks" => {
+                let value = value.parse::<bool>().unwrap();
+                self.blank_lines_around_blocks = value;
+            }
+            "remove_leading_blank_lines" => {
+                let value = value.parse::<bool>().unwrap();
+                self.remove_leading_blank_lines = value;
+            }
+            "hard_break" => {
+                self.hard_break = match value {
+                    "backslash" => HardBreak::Backslash,
+                    "two_spaces" => HardBreak::TwoSpaces,
+                    "preserve" => HardBreak::Preserve,
+                    _ => panic!("unknown hard_break value {value}"),
+                };
+            }
             _ => panic!("unknown configuration {field}"),
         }
     }
@@ -0,0 +1,170 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+/// The kind of problem that a [Diagnostic] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// A reference link or image whose label doesn't match any link reference definition.
+    ///
+    /// Shortcut references, like `[this]`, are only reported when
+    /// [`FormatBuilder::report_undefined_shortcut_references`] is enabled, since text in square
+    /// brackets is usually prose and not a broken link.
+    ///
+    /// [`FormatBuilder::report_undefined_shortcut_references`]: crate::FormatBuilder::report_undefined_shortcut_references
+    UndefinedReference,
+    /// A link reference definition that isn't used by any link or image.
+    UnusedDefinition,
+    /// A link reference definition whose label was already defined. Only the first definition
+    /// of a label is ever used.
+    DuplicateDefinition,
+    /// A footnote definition that isn't referenced anywhere. Footnote references in footnote
+    /// definitions only count if that footnote definition is referenced.
+    UnusedFootnoteDefinition,
+}
+
+/// A problem found in the Markdown input while formatting.
+///
+/// See [MarkdownFormatter::format_with_diagnostics].
+///
+/// [MarkdownFormatter::format_with_diagnostics]: crate::MarkdownFormatter::format_with_diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    kind: DiagnosticKind,
+    label: String,
+    range: Range<usize>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(kind: DiagnosticKind, label: String, range: Range<usize>) -> Self {
+        Self { kind, label, range }
+    }
+
+    /// Map both ends of the range to offsets in a different version of the input.
+    pub(crate) fn map_range(&mut self, f: impl Fn(usize) -> usize) {
+        self.range = f(self.range.start)..f(self.range.end);
+    }
+
+    /// The kind of problem.
+    pub fn kind(&self) -> DiagnosticKind {
+        self.kind
+    }
+
+    /// The label of the link, link reference definition, or footnote definition, as it was
+    /// written in the input.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The byte range of the link, link reference definition, or footnote definition in the
+    /// input.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { kind, label, range } = self;
+        match kind {
+            DiagnosticKind::UndefinedReference => write!(f, "undefined link reference `{label}`")?,
+            DiagnosticKind::UnusedDefinition => {
+                write!(f, "unused link reference definition `{label}`")?
+            }
+            DiagnosticKind::DuplicateDefinition => {
+                write!(f, "duplicate link reference definition `{label}`")?
+            }
+            DiagnosticKind::UnusedFootnoteDefinition => {
+                write!(f, "unused footnote definition `{label}`")?
+            }
+        }
+        write!(f, " at {}..{}", range.start, range.end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DiagnosticKind::*;
+    use crate::FormatBuilder;
+
+    fn check(input: &str, expected: &[(super::DiagnosticKind, &str, &str)]) {
+        let formatter = FormatBuilder::default().build();
+        let (_, diagnostics) = formatter.format_with_diagnostics(input).unwrap();
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| (d.kind(), d.label(), &input[d.range()]))
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics, expected);
+    }
+
+    #[test]
+    fn no_diagnostics_when_all_links_resolve() {
+        check("[a], [b][], ![c][C]\n\n[a]: /a\n[b]: /b\n[c]: /c", &[]);
+    }
+
+    #[test]
+    fn nested_definitions_and_references() {
+        let input =
+            "> [quoted]: /q 'Title'\n\n- [missing][]\n\n  [item\n  label]: </i>\n- ![img][]";
+        check(
+            input,
+            &[
+                (UnusedDefinition, "quoted", "[quoted]: /q 'Title'"),
+                (UndefinedReference, "missing", "[missing][]"),
+                (UnusedDefinition, "item label", "[item\n  label]: </i>"),
+                (UndefinedReference, "img", "![img][]"),
+            ],
+        );
+    }
+
+    #[test]
+    fn bracketed_prose_is_not_reported() {
+        check(
+            "Read array[0], not [sic] or [x].\n\n- [ ] task\n- [x] done",
+            &[],
+        );
+    }
+
+    #[test]
+    fn undefined_shortcut_references_are_reported_when_enabled() {
+        let input = "[missing] and ![img]\n\n[a]: /a";
+        let mut builder = FormatBuilder::default();
+        builder.report_undefined_shortcut_references(true);
+        let (_, diagnostics) = builder.build().format_with_diagnostics(input).unwrap();
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| (d.kind(), d.label(), &input[d.range()]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [
+                (UndefinedReference, "missing", "[missing]"),
+                (UndefinedReference, "img", "![img]"),
+                (UnusedDefinition, "a", "[a]: /a"),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_reported_even_when_the_label_is_used() {
+        let input = "[Foo]\n\n[foo]: /a\n[FOO]: /b\n\n> [foo]: /c";
+        check(
+            input,
+            &[
+                (DuplicateDefinition, "FOO", "[FOO]: /b"),
+                (DuplicateDefinition, "foo", "[foo]: /c"),
+            ],
+        );
+    }
+
+    #[test]
+    fn footnotes_referenced_only_by_unused_footnotes() {
+        let input = "Text[^a]\n\n[^a]: A[^b]\n[^b]: B\n[^c]: C[^d]\n[^d]: D[^c]";
+        check(
+            input,
+            &[
+                (UnusedFootnoteDefinition, "c", "[^c]: C[^d]"),
+                (UnusedFootnoteDefinition, "d", "[^d]: D[^c]"),
+            ],
+        );
+    }
+}
@@ -1,8 +1,23 @@
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
+use crate::links::normalize_link_label;
+
 static FOOTNOTE_INDENTATION: &str = "    ";
 
+/// Check if the formatted content of a footnote definition is a single paragraph, which can be
+/// written on the same line as the label and indented by less than four spaces.
+pub(crate) fn is_single_paragraph(content: &str) -> bool {
+    let mut events = Parser::new_ext(content, crate::pulldown_cmark_options!()).into_offset_iter();
+    matches!(
+        events.next(),
+        Some((Event::Start(Tag::Paragraph), range))
+            if range.start == 0 && range.end >= content.trim_end().len()
+    )
+}
+
 /// A buffer where we write footnote definition text
 #[derive(Debug, PartialEq)]
 pub(crate) struct FootnoteDefinition {
@@ -25,13 +40,209 @@ impl FootnoteDefinition {
         }
     }
 
+    /// Get the text that's been written so far
+    pub(super) fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
     /// Check if the internal buffer is empty
     pub(super) fn is_empty(&self) -> bool {
         self.buffer.is_empty()
     }
 
+    /// Remove spaces and tabs from the end of the buffer
+    pub(super) fn trim_end_spaces(&mut self) {
+        let len = self.buffer.trim_end_matches([' ', '\t']).len();
+        self.buffer.truncate(len);
+    }
+
     /// Consume Self and return the formatted buffer
     pub(super) fn into_parts(self) -> (String, Vec<Cow<'static, str>>, Cow<'static, str>) {
         (self.buffer, self.indentation, FOOTNOTE_INDENTATION.into())
     }
 }
+
+/// Keeps track of the footnotes that each footnote definition references.
+#[derive(Debug, Default)]
+pub(crate) struct FootnoteReferences {
+    /// Normalized labels of every footnote definition, in the order they're defined.
+    definitions: Vec<String>,
+    /// Normalized labels that are referenced outside of any footnote definition.
+    document_references: Vec<String>,
+    /// Normalized labels that are referenced by each footnote definition.
+    definition_references: HashMap<String, Vec<String>>,
+    /// The normalized label of the footnote definition that we're currently in.
+    current_definition: Option<String>,
+}
+
+impl FootnoteReferences {
+    pub(crate) fn start_definition(&mut self, label: &str) {
+        let label = normalize_link_label(label);
+        self.definitions.push(label.clone());
+        self.current_definition = Some(label);
+    }
+
+    pub(crate) fn end_definition(&mut self) {
+        self.current_definition = None;
+    }
+
+    pub(crate) fn add_reference(&mut self, label: &str) {
+        let label = normalize_link_label(label);
+        match &self.current_definition {
+            Some(definition) => self
+                .definition_references
+                .entry(definition.clone())
+                .or_default()
+                .push(label),
+            None => self.document_references.push(label),
+        }
+    }
+
+    /// Order the footnote definitions by when they're first referenced, like pulldown-cmark
+    /// numbers footnotes. All references outside of footnote definitions come first, then the
+    /// references in the definitions they lead to, breadth first. Footnote definitions that
+    /// can't be reached from the rest of the document come last in source order, each followed
+    /// by the definitions it leads to.
+    ///
+    /// Returns the ordered labels along with the number of footnotes that are referenced.
+    fn order(&self) -> (Vec<String>, usize) {
+        let defined = self.definitions.iter().collect::<HashSet<_>>();
+        let mut order = vec![];
+        let mut seen = HashSet::new();
+        let mut visit = |label: &String, order: &mut Vec<String>| {
+            if defined.contains(label) && seen.insert(label.clone()) {
+                order.push(label.clone());
+            }
+        };
+
+        for label in &self.document_references {
+            visit(label, &mut order);
+        }
+
+        let mut referenced = None;
+        let mut next = 0;
+        let mut definitions = self.definitions.iter();
+        loop {
+            while let Some(label) = order.get(next).cloned() {
+                next += 1;
+                for nested in self.definition_references.get(&label).into_iter().flatten() {
+                    visit(nested, &mut order);
+                }
+            }
+            referenced.get_or_insert(order.len());
+            match definitions.next() {
+                Some(label) => visit(label, &mut order),
+                None => break,
+            }
+        }
+
+        (order, referenced.unwrap_or_default())
+    }
+}
+
+/// Keeps track of the order that footnotes are first referenced in, and of the labels that
+/// numeric footnotes are renumbered to.
+pub(crate) struct Footnotes {
+    /// Where each footnote definition is in the order that footnotes are first referenced.
+    /// Keyed by the normalized label.
+    reference_order: HashMap<String, usize>,
+    /// The number of footnote definitions that are referenced. They're first in the order.
+    referenced: usize,
+    /// Labels that numeric footnotes are written with. Keyed by the normalized label.
+    renumbered_labels: HashMap<String, String>,
+}
+
+impl Footnotes {
+    /// Find the footnote references in the `input`.
+    pub(crate) fn new(input: &str, renumber: bool) -> Self {
+        let mut references = FootnoteReferences::default();
+        for event in Parser::new_ext(input, crate::pulldown_cmark_options!()) {
+            match event {
+                Event::FootnoteReference(label) => references.add_reference(&label),
+                Event::Start(Tag::FootnoteDefinition(label)) => references.start_definition(&label),
+                Event::End(TagEnd::FootnoteDefinition) => references.end_definition(),
+                _ => {}
+            }
+        }
+
+        let mut footnotes = Self::from_references(&references);
+        if renumber {
+            footnotes.renumber(input, &references.definitions);
+        }
+        footnotes
+    }
+
+    pub(crate) fn from_references(references: &FootnoteReferences) -> Self {
+        let (order, referenced) = references.order();
+        let reference_order = order
+            .into_iter()
+            .enumerate()
+            .map(|(idx, label)| (label, idx))
+            .collect();
+        Self {
+            reference_order,
+            referenced,
+            renumbered_labels: HashMap::new(),
+        }
+    }
+
+    /// Number footnotes with numeric labels in the order they're first referenced. Footnotes
+    /// that are never referenced are numbered last.
+    fn renumber(&mut self, input: &str, definitions: &[String]) {
+        let is_numeric = |label: &str| label.bytes().all(|b| b.is_ascii_digit());
+
+        // Text like `[^3]` turns into a footnote reference if `3` gets defined
+        let defined = definitions.iter().collect::<HashSet<_>>();
+        let taken_labels = input
+            .match_indices("[^")
+            .filter_map(|(idx, _)| {
+                let rest = &input[idx + 2..];
+                let end = rest.find(|c: char| !c.is_ascii_digit())?;
+                let label = rest[..end].to_owned();
+                (end > 0 && rest[end..].starts_with(']') && !defined.contains(&label))
+                    .then_some(label)
+            })
+            .collect::<HashSet<_>>();
+
+        let mut numeric_labels = self
+            .reference_order
+            .iter()
+            .filter(|(label, _)| is_numeric(label))
+            .collect::<Vec<_>>();
+        numeric_labels.sort_by_key(|(_, order)| **order);
+
+        let mut number = 0;
+        for (label, _) in numeric_labels {
+            let new_label = loop {
+                number += 1;
+                let new_label = number.to_string();
+                if !taken_labels.contains(&new_label) {
+                    break new_label;
+                }
+            };
+            self.renumbered_labels.insert(label.clone(), new_label);
+        }
+    }
+
+    /// Where the footnote definition is in the order that footnotes are first referenced.
+    pub(crate) fn reference_order(&self, label: &str) -> usize {
+        self.reference_order
+            .get(&normalize_link_label(label))
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Check if the footnote is referenced anywhere other than from footnote definitions that
+    /// aren't referenced themselves.
+    pub(crate) fn is_referenced(&self, label: &str) -> bool {
+        self.reference_order(label) < self.referenced
+    }
+
+    /// The label that a footnote reference or definition should be written with.
+    pub(crate) fn label<'a>(&self, label: &'a str) -> Cow<'a, str> {
+        match self.renumbered_labels.get(&normalize_link_label(label)) {
+            Some(new_label) => Cow::from(new_label.clone()),
+            None => Cow::from(label),
+        }
+    }
+}
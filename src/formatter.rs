@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::fmt::Write;
 use std::iter::Peekable;
 use std::ops::Range;
@@ -11,21 +12,37 @@ use pulldown_cmark::{LinkType, Parser, Tag};
 
 use crate::adapters::{ListEndAtLastItemExt, LooseListExt};
 use crate::builder::{CodeBlockContext, CodeBlockFormatter};
-use crate::config::Config;
-use crate::footnote::FootnoteDefinition;
+use crate::config::{
+    BlockQuoteStyle, BulletStyle, CodeFenceStyle, Config, EmphasisMarker,
+    FootnoteDefinitionPlacement, FootnoteIndentation, HardBreak, HeadingStyle, LineEnding,
+    LinkDefinitionPlacement, LinkStyle, LinkTitleStyle, ListIndent, ListSpacing,
+    OrderedListDelimiter, OrderedListNumbering, StrikethroughMarker, ThematicBreak,
+};
+use crate::diagnostics::{Diagnostic, DiagnosticKind};
+use crate::footnote::{FootnoteDefinition, FootnoteReferences, Footnotes, is_single_paragraph};
 use crate::header::{Header, HeaderKind};
-use crate::html::starts_with_html_block_identifier;
-use crate::links::{LinkReferenceDefinition, LinkWriter, parse_link_reference_definitions};
-use crate::list::{LIST_START_CHARS, ListMarker};
+use crate::html::{is_unterminated_html_block, starts_with_html_block_identifier};
+use crate::links::{
+    LinkReferenceDefinition, LinkReferences, LinkWriter, TitleMarker, normalize_link_label,
+    parse_link_reference_definitions,
+};
+use crate::list::{
+    ListMarker, OrderedListMarker, UnorderedListMarker, footnote_definitions_ending_lists,
+    list_marker_start, lists_with_blank_lines_in_items, loose_lists,
+};
 use crate::paragraph::Paragraph;
 use crate::table::TableState;
 use crate::utils::{
-    count_newlines, count_trailing_spaces, get_spaces, sequence_ends_on_escape, split_lines,
+    count_newlines, count_trailing_spaces, crlf_offsets, expand_leading_tabs, get_spaces,
+    is_thematic_break, most_common_line_ending, next_tab_stop, replace_line_endings,
+    sequence_ends_on_escape, split_lines, trim_start_columns, unicode_str_width,
 };
 use crate::writer::{
     MarkdownContext, MarkdownWriter, WriteContext, write_context, writeln_context,
 };
 
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
 // Defined using a macro so that the parsing options can be shared with tests for consistency.
 #[doc(hidden)]
 #[macro_export]
@@ -63,12 +80,71 @@ impl MarkdownFormatter {
     /// assert_eq!(rewrite, String::from("# Header!"));
     /// ```
     pub fn format(&self, input: &str) -> Result<String, std::fmt::Error> {
+        self.format_with_diagnostics(input)
+            .map(|(output, _)| output)
+    }
+
+    /// Format Markdown input and report problems with links along the way.
+    ///
+    /// The [Diagnostic]s are ordered by where they're found, and their ranges point into the
+    /// `input`, not the formatted output. Use them to fail CI checks on Markdown with broken
+    /// references or stale link reference definitions.
+    ///
+    /// ```rust
+    /// # use markdown_fmt::{DiagnosticKind, FormatBuilder};
+    /// let formatter = FormatBuilder::default().build();
+    /// let input = "[used] and [missing][]\n\n[used]: /a\n[unused]: /b\n[used]: /c";
+    /// let (output, diagnostics) = formatter.format_with_diagnostics(input).unwrap();
+    /// assert_eq!(output, input);
+    ///
+    /// let problems = diagnostics
+    ///     .iter()
+    ///     .map(|d| (d.kind(), d.label(), &input[d.range()]))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(problems, [
+    ///     (DiagnosticKind::UndefinedReference, "missing", "[missing][]"),
+    ///     (DiagnosticKind::UnusedDefinition, "unused", "[unused]: /b"),
+    ///     (DiagnosticKind::DuplicateDefinition, "used", "[used]: /c"),
+    /// ]);
+    /// assert_eq!(
+    ///     diagnostics[0].to_string(),
+    ///     "undefined link reference `missing` at 11..22"
+    /// );
+    /// ```
+    ///
+    /// [Diagnostic]: crate::Diagnostic
+    pub fn format_with_diagnostics(
+        &self,
+        input: &str,
+    ) -> Result<(String, Vec<Diagnostic>), std::fmt::Error> {
         // callback that will always revcover broken links
         let mut callback = |broken_link| {
             tracing::trace!("found boken link: {broken_link:?}");
             Some(("".into(), "".into()))
         };
 
+        // The parser doesn't skip over a byte order mark, so it would end up in the first block
+        let (has_byte_order_mark, input) = match input.strip_prefix(BYTE_ORDER_MARK) {
+            Some(input) => (true, input),
+            None => (false, input),
+        };
+
+        let line_ending = match self.get_config(|c| c.line_ending()) {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Preserve => most_common_line_ending(input),
+        };
+
+        // The parser doesn't handle `\r\n` the same way as `\n` everywhere, like in HTML blocks
+        // that are nested in other blocks, so the input is formatted with `\n` line endings.
+        let normalized_input;
+        let (input, crlf_newlines) = if input.contains('\r') {
+            normalized_input = replace_line_endings(input, "\n");
+            (normalized_input.as_str(), crlf_offsets(input))
+        } else {
+            (input, vec![])
+        };
+
         let options = pulldown_cmark_options!();
 
         let parser = Parser::new_with_broken_link_callback(input, options, Some(&mut callback));
@@ -78,7 +154,30 @@ impl MarkdownFormatter {
             .list_end_at_last_item();
 
         let fmt_state = FormatState::new(input, self, iter);
-        fmt_state.format()
+        let (mut output, mut diagnostics) = fmt_state.format_with_diagnostics()?;
+
+        if line_ending != "\n" {
+            output = replace_line_endings(&output, line_ending);
+        }
+
+        // Diagnostics should point into the input as it was given
+        if !crlf_newlines.is_empty() {
+            for diagnostic in diagnostics.iter_mut() {
+                diagnostic.map_range(|offset| {
+                    offset + crlf_newlines.partition_point(|&newline| newline < offset)
+                });
+            }
+        }
+
+        if has_byte_order_mark {
+            for diagnostic in diagnostics.iter_mut() {
+                diagnostic.map_range(|offset| offset + BYTE_ORDER_MARK.len_utf8());
+            }
+            if !self.get_config(|c| c.remove_byte_order_mark()) {
+                output.insert(0, BYTE_ORDER_MARK);
+            }
+        }
+        Ok((output, diagnostics))
     }
 
     pub(crate) fn get_config<F, O>(&self, f: F) -> O
@@ -121,8 +220,23 @@ where
     /// Stack that keeps track of nested list markers.
     /// Unordered list markers are one of `*`, `+`, or `-`,
     /// while ordered lists markers start with 0-9 digits followed by a `.` or `)`.
-    // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-    // list_markers: Vec<ListMarker>,
+    list_markers: Vec<ListMarker>,
+    /// Stack that keeps track of whether nested lists are written with a blank line between
+    /// their items. `None` if the blank lines from the source are kept.
+    loose_lists: Vec<Option<bool>>,
+    /// Where each list that has a list item with a blank line in it starts. Those lists are
+    /// always loose.
+    lists_with_blank_lines_in_items: HashSet<usize>,
+    /// Where each loose list in the source starts. Only used to add blank lines around blocks.
+    source_loose_lists: HashSet<usize>,
+    /// Stack that keeps track of whether nested lists are tight. Blank lines can't be added
+    /// between the blocks in a tight list's items without making the list loose.
+    tight_lists: Vec<bool>,
+    /// Whether the last code block was written as a fenced code block.
+    last_code_block_was_fenced: bool,
+    /// Whether an HTML comment that separates two lists was just written, and the next list
+    /// needs a blank line before it.
+    wrote_list_separator: bool,
     /// Stack that keeps track of indentation.
     indentation: Vec<Cow<'static, str>>,
     /// Stack that keeps track of whether we're formatting inside of another element.
@@ -138,10 +252,40 @@ where
     /// ```markdown
     /// [title]: link "optional title"
     /// ```
-    #[allow(dead_code)]
-    // TODO(ytmimi) will come in handy when adding an option to defer
-    // rewriting link reference definitions until the end of the document
+    ///
+    /// Only used when link reference definitions are moved to the end of the document or section.
     reference_links: Vec<LinkReferenceDefinition<'i>>,
+    /// The end of the last link reference or footnote definitions that were deferred or removed
+    /// if a blank line came before them. Keeping that blank line prevents a loose list from
+    /// turning into a tight list.
+    blank_line_before_skipped_definitions: Option<usize>,
+    /// Normalized labels of all the link reference definitions we've seen so far.
+    link_labels: HashSet<String>,
+    /// Normalized labels used by reference links and images.
+    used_link_labels: HashSet<String>,
+    /// The first definition of each normalized label. Reported if no link uses the label.
+    link_definitions: Vec<(String, Diagnostic)>,
+    /// The footnotes referenced from the document and from each footnote definition.
+    footnote_references: FootnoteReferences,
+    /// Every footnote definition's label. Reported if nothing references the label.
+    footnote_definitions: Vec<(String, Diagnostic)>,
+    /// Used to move, renumber, and remove footnote definitions.
+    footnotes: Option<Footnotes>,
+    /// The label of the footnote definition that's currently being formatted if it's moved to
+    /// the end of the document (`true`) or removed (`false`), along with the number of newlines
+    /// that came before it.
+    skipped_footnote_definition: Option<(String, bool, usize)>,
+    /// Where each footnote definition that's the last block in the last item of a list starts.
+    /// Those definitions stay in the list if there's a blank line before them.
+    footnote_definitions_ending_lists: HashSet<usize>,
+    /// Formatted footnote definitions that are written at the end of the document, along with
+    /// where they are in the order that footnotes are first referenced.
+    deferred_footnote_definitions: Vec<(usize, String)>,
+    /// Problems found in the input.
+    diagnostics: Vec<Diagnostic>,
+    /// Used to convert links when they're not written in the style they had in the source, and
+    /// to remove unused link reference definitions.
+    link_references: Option<LinkReferences<'i>>,
     /// next Start event should push indentation
     needs_indent: bool,
     last_position: usize,
@@ -199,6 +343,21 @@ where
 
     /// Check if we should write newlines and indentation before the next Start Event
     fn check_needs_indent(&mut self, event: &Event<'i>) {
+        if self.in_html_block() {
+            // Text in an HTML block is what's left of a tab that was partly used as indentation,
+            // so it starts a new line that continues with the next `Event::Html`.
+            match (event, self.peek()) {
+                (Event::Html(_), Some(Event::Text(_))) => {
+                    self.needs_indent = true;
+                    return;
+                }
+                (Event::Text(_), Some(Event::Html(_))) => {
+                    self.needs_indent = false;
+                    return;
+                }
+                _ => {}
+            }
+        }
         self.needs_indent = match self.peek() {
             Some(Event::Start(_) | Event::Rule | Event::Html(_) | Event::End(TagEnd::Item)) => true,
             Some(Event::End(TagEnd::BlockQuote(..))) => matches!(event, Event::End(_)),
@@ -242,11 +401,35 @@ where
             .is_some_and(|w| matches!(w, MarkdownWriter::Paragraph(_)))
     }
 
-    /// Check if we're in a Table
-    fn in_table(&self) -> bool {
-        self.writers
-            .last()
-            .is_some_and(|w| matches!(w, MarkdownWriter::Table(_)))
+    /// Check if indented code blocks should be written as fenced code blocks
+    fn fence_indented_code_blocks(&self) -> bool {
+        self.formatter
+            .get_config(|c| c.fence_indented_code_blocks().is_some())
+    }
+
+    /// The marker written for a block quote when a nested block quote starts on the same line.
+    fn nested_blockquote_marker(&self) -> &'static str {
+        match self.formatter.get_config(|c| c.blockquote_style()) {
+            BlockQuoteStyle::Compact => ">",
+            BlockQuoteStyle::Spaced => "> ",
+        }
+    }
+
+    /// The configured [ListIndent] for the list item that's about to be written.
+    ///
+    /// The content of empty list items and list items that start with an indented code block
+    /// is always indented by the width of the list marker, since the content column of those
+    /// list items doesn't depend on the spaces that follow the marker.
+    fn list_indent(&mut self, empty_list_item: bool) -> ListIndent {
+        let starts_with_indented_code_block = matches!(
+            self.events.peek(),
+            Some((Event::Start(Tag::CodeBlock(CodeBlockKind::Indented)), _))
+        );
+        if empty_list_item || starts_with_indented_code_block && !self.fence_indented_code_blocks()
+        {
+            return ListIndent::MarkerWidth;
+        }
+        self.formatter.get_config(|c| c.list_indent())
     }
 
     /// Check if we're formatting in a nested context
@@ -259,6 +442,42 @@ where
         self.indentation.iter().map(|i| i.len()).sum()
     }
 
+    /// Get the last line of the buffer we're currently writing to.
+    ///
+    /// Only the main rewrite buffer and footnote definitions can contain block level elements
+    /// like lists, so the line is empty when writing to any other buffer.
+    fn current_line(&self) -> &str {
+        let buffer = match self.writers.last() {
+            Some(MarkdownWriter::FootnoteDefinition(f)) => f.as_str(),
+            Some(_) => return "",
+            None => self.rewrite_buffer.as_str(),
+        };
+        buffer.rsplit(['\r', '\n']).next().unwrap_or_default()
+    }
+
+    /// Remove spaces and tabs from the end of the current line, like the space after a `>` that's
+    /// left behind when the rest of the line isn't written.
+    fn trim_end_spaces_on_current_line(&mut self) {
+        match self.writers.last_mut() {
+            Some(MarkdownWriter::FootnoteDefinition(f)) => f.trim_end_spaces(),
+            Some(_) => {}
+            None => {
+                let len = self.rewrite_buffer.trim_end_matches([' ', '\t']).len();
+                self.rewrite_buffer.truncate(len);
+            }
+        }
+    }
+
+    /// Count how many times `c` is repeated at the end of the current line, ignoring whitespace.
+    fn count_trailing_chars_on_current_line(&self, c: char) -> usize {
+        self.current_line()
+            .chars()
+            .rev()
+            .take_while(|ch| *ch == c || ch.is_whitespace())
+            .filter(|ch| *ch == c)
+            .count()
+    }
+
     /// Get an exclusive reference to the current buffer we're writing to. That could be the main
     /// rewrite buffer, the code block buffer, the internal table state, or anything else we're
     /// writing to while reformatting
@@ -297,7 +516,77 @@ where
             // likely in some nested context
             self.input[self.last_position..range.end].trim_end_matches(['\r', '\n'])
         };
-        count_newlines(snippet)
+        let mut newlines = count_newlines(snippet);
+
+        if newlines > 0 && self.blank_line_before_skipped_definitions == Some(self.last_position) {
+            // Keep the blank line that came before the deferred or removed definitions
+            newlines = newlines.max(2);
+        }
+
+        // Blank lines in HTML blocks and code blocks are part of their content
+        let in_code_block = matches!(self.writers.last(), Some(MarkdownWriter::CodeBlock(_)));
+        match self.formatter.get_config(|c| c.max_blank_lines()) {
+            Some(max) if !self.in_html_block() && !in_code_block => {
+                // Removing every blank line would merge blocks like paragraphs together
+                newlines.min(max.max(1) + 1)
+            }
+            _ => newlines,
+        }
+    }
+
+    /// Count the newlines between the start of a block quote and its first block. Blank lines at
+    /// the start of a block quote are removed if that's configured.
+    fn leading_newlines_in_block_quote(&self, snippet: &str) -> usize {
+        if self
+            .formatter
+            .get_config(|c| c.remove_leading_blank_lines())
+        {
+            0
+        } else {
+            count_newlines(snippet)
+        }
+    }
+
+    /// Count the newlines before a block or link reference definition.
+    ///
+    /// Headings, fenced code blocks, tables, and HTML blocks are separated from the blocks around
+    /// them by a blank line if that's configured. `needs_blank_line` is `true` for those blocks.
+    fn count_newlines_before_block(
+        &mut self,
+        range: &Range<usize>,
+        needs_blank_line: bool,
+    ) -> usize {
+        let needs_blank_line = std::mem::take(&mut self.wrote_list_separator) || needs_blank_line;
+        let newlines = self.count_newlines(range);
+        if newlines != 1 || !self.formatter.get_config(|c| c.blank_lines_around_blocks()) {
+            return newlines;
+        }
+
+        let needs_blank_line_after = match &self.last_event {
+            // Link reference definitions might've been written since the block ended
+            Some((event, r)) if self.last_position <= r.end => match event {
+                Event::End(TagEnd::Heading(_) | TagEnd::Table | TagEnd::HtmlBlock) => true,
+                Event::End(TagEnd::CodeBlock) => self.last_code_block_was_fenced,
+                _ => false,
+            },
+            _ => false,
+        };
+        // Only blocks that follow another block in the same container are separated
+        let follows_block = matches!(self.last_event, Some((Event::End(_) | Event::Rule, _)));
+        // A blank line between the blocks of a tight list's item would make the list loose
+        let in_tight_list_item = self
+            .nested_context
+            .iter()
+            .rev()
+            .find(|t| matches!(t, Tag::Item | Tag::BlockQuote(_)))
+            .is_some_and(|t| matches!(t, Tag::Item))
+            && self.tight_lists.last().copied().unwrap_or_default();
+
+        if (needs_blank_line || needs_blank_line_after) && follows_block && !in_tight_list_item {
+            2
+        } else {
+            newlines
+        }
     }
 
     fn write_indentation(&mut self, trim_trailing_whiltespace: bool) -> std::fmt::Result {
@@ -378,22 +667,146 @@ where
         Ok(())
     }
 
+    /// Write link reference definitions that were found before the next event.
+    ///
+    /// Definitions might be moved or removed depending on the configuration, unless they come
+    /// after a blank line and `can_move_after_blank_line` is `false`.
     fn rewrite_reference_link_definitions_inner(
         &mut self,
-        link_defs: Vec<LinkReferenceDefinition>,
+        link_defs: Vec<LinkReferenceDefinition<'i>>,
+        can_move_after_blank_line: bool,
     ) -> std::fmt::Result {
-        // TODO(ytmimi) Add an option to defer writing links until the end
-        for link_def in link_defs {
-            let link_range = link_def.range();
-            let newlines = self.count_newlines(&link_range);
-            self.write_newlines(newlines)?;
-            link_def.write(self)?;
-            self.last_position = link_range.end;
-            self.needs_indent = true;
+        let (Some(first), Some(last)) = (link_defs.first(), link_defs.last()) else {
+            return Ok(());
+        };
+        let can_move = can_move_after_blank_line || self.count_newlines(&first.range()) < 2;
+        let leading_newlines = self.count_newlines_before_block(&first.range(), false);
+        let end = last.range().end;
+
+        // The first definition for a label wins, so later duplicates can be removed
+        let deduplicate = can_move
+            && self
+                .formatter
+                .get_config(|c| c.deduplicate_link_definitions());
+        let link_defs = link_defs
+            .into_iter()
+            .map(|link_def| {
+                let label = link_def.normalized_label();
+                let is_unused = self.is_unused_reference_link_definition(&label);
+                let is_first_definition = self.link_labels.insert(label.clone());
+                let diagnostic = |kind| Diagnostic::new(kind, link_def.label(), link_def.span());
+                if is_first_definition {
+                    let diagnostic = diagnostic(DiagnosticKind::UnusedDefinition);
+                    self.link_definitions.push((label, diagnostic));
+                } else {
+                    let diagnostic = diagnostic(DiagnosticKind::DuplicateDefinition);
+                    self.diagnostics.push(diagnostic);
+                }
+                let link_def = self.normalize_reference_link_definition(link_def);
+                let keep = (is_first_definition || !deduplicate) && !(can_move && is_unused);
+                (link_def, keep)
+            })
+            .collect::<Vec<_>>();
+
+        let defer = can_move && self.defer_reference_link_definitions();
+        if defer || !link_defs.iter().any(|(_, keep)| *keep) {
+            // Nothing gets written, but move past the definitions so they're only collected once
+            self.last_position = end;
+            self.blank_line_before_skipped_definitions = (leading_newlines > 1).then_some(end);
+            if defer {
+                let link_defs = link_defs
+                    .into_iter()
+                    .filter_map(|(l, keep)| keep.then_some(l));
+                self.reference_links.extend(link_defs);
+            }
+            return Ok(());
+        }
+
+        if self.formatter.get_config(|c| c.sort_link_definitions()) {
+            let mut link_defs = link_defs
+                .into_iter()
+                .filter_map(|(l, keep)| keep.then_some(l))
+                .collect::<Vec<_>>();
+            link_defs.sort_by_cached_key(|l| l.normalized_label());
+            self.write_newlines(leading_newlines)?;
+            for (index, link_def) in link_defs.into_iter().enumerate() {
+                if index > 0 {
+                    self.write_newlines(1)?;
+                }
+                link_def.write(self)?;
+            }
+        } else {
+            let mut is_first = true;
+            for (link_def, keep) in link_defs {
+                let link_range = link_def.range();
+                if keep {
+                    let newlines = if is_first {
+                        // Removed duplicates shouldn't change how far we are from the last block
+                        leading_newlines
+                    } else {
+                        self.count_newlines(&link_range)
+                    };
+                    self.write_newlines(newlines)?;
+                    link_def.write(self)?;
+                    is_first = false;
+                }
+                self.last_position = link_range.end;
+            }
         }
+
+        self.last_position = end;
+        self.needs_indent = true;
         Ok(())
     }
 
+    /// Apply the configured label and title normalizations to a link reference definition.
+    fn normalize_reference_link_definition(
+        &self,
+        mut link_def: LinkReferenceDefinition<'i>,
+    ) -> LinkReferenceDefinition<'i> {
+        let (normalize_label, title_style) = self
+            .formatter
+            .get_config(|c| (c.normalize_link_labels(), c.link_title_style()));
+
+        if normalize_label {
+            link_def.normalize_label();
+        }
+
+        let title_marker = match title_style {
+            LinkTitleStyle::DoubleQuote => Some(TitleMarker::DoubleQuote),
+            LinkTitleStyle::SingleQuote => Some(TitleMarker::SingleQuote),
+            LinkTitleStyle::Parentheses => Some(TitleMarker::Parentheses),
+            LinkTitleStyle::Preserve => None,
+        };
+        if let Some(marker) = title_marker {
+            link_def.set_title_marker(marker);
+        }
+        link_def
+    }
+
+    /// Check if any of the link reference definitions will be written where they were defined.
+    fn writes_reference_link_definitions(&self, link_defs: &[LinkReferenceDefinition]) -> bool {
+        if self.defer_reference_link_definitions() {
+            return false;
+        }
+        let deduplicate = self
+            .formatter
+            .get_config(|c| c.deduplicate_link_definitions());
+        link_defs.iter().any(|l| {
+            let label = l.normalized_label();
+            let is_duplicate = deduplicate && self.link_labels.contains(&label);
+            !is_duplicate && !self.is_unused_reference_link_definition(&label)
+        })
+    }
+
+    /// Check if a link reference definition should be removed because no link uses it once
+    /// links are written in the configured style.
+    fn is_unused_reference_link_definition(&self, normalized_label: &str) -> bool {
+        self.link_references
+            .as_ref()
+            .is_some_and(|r| r.is_unused(normalized_label))
+    }
+
     fn rewrite_reference_link_definitions(&mut self, range: &Range<usize>) -> std::fmt::Result {
         let snippet = &self.input[range.clone()];
         let link_defs = parse_link_reference_definitions(snippet, range.start);
@@ -401,14 +814,129 @@ where
         if link_defs.is_empty() {
             return Ok(());
         }
-        self.rewrite_reference_link_definitions_inner(link_defs)
+        self.rewrite_reference_link_definitions_inner(link_defs, true)
+    }
+
+    /// Check if link reference definitions should be written at the end of the document or
+    /// section instead of where they were defined.
+    fn defer_reference_link_definitions(&self) -> bool {
+        let placement = self.formatter.get_config(|c| c.link_definition_placement());
+        !matches!(placement, LinkDefinitionPlacement::Preserve)
+    }
+
+    /// Write out all the link reference definitions that were deferred.
+    fn write_deferred_reference_link_definitions(&mut self) -> std::fmt::Result {
+        let mut link_defs = std::mem::take(&mut self.reference_links);
+        if self.formatter.get_config(|c| c.sort_link_definitions()) {
+            link_defs.sort_by_cached_key(|l| l.normalized_label());
+        }
+        for (index, link_def) in link_defs.into_iter().enumerate() {
+            // Separate the definitions from any preceding block with a blank line
+            let newlines = if index == 0 { 2 } else { 1 };
+            self.write_newlines(newlines)?;
+            link_def.write(self)?;
+            self.needs_indent = true;
+        }
+        Ok(())
+    }
+
+    /// Write out the deferred link reference definitions before a top level block.
+    /// Returns `true` if any definitions were written.
+    fn write_deferred_reference_link_definitions_before_block(
+        &mut self,
+    ) -> Result<bool, std::fmt::Error> {
+        let is_top_level = self.nested_context.is_empty() && self.writers.is_empty();
+        if !is_top_level || self.reference_links.is_empty() {
+            return Ok(false);
+        }
+        self.write_deferred_reference_link_definitions()?;
+        Ok(true)
     }
 
     /// Write out reference links at the end of the file
-    fn rewrite_final_reference_links(mut self) -> Result<String, std::fmt::Error> {
+    fn rewrite_final_reference_links(&mut self) -> std::fmt::Result {
         let range = self.last_position..self.input.len();
         self.rewrite_reference_link_definitions(&range)?;
-        Ok(self.rewrite_buffer)
+        self.write_deferred_reference_link_definitions()?;
+        self.write_deferred_footnote_definitions()
+    }
+
+    /// The label that a footnote reference or definition should be written with.
+    fn footnote_label<'a>(&self, label: &'a str) -> Cow<'a, str> {
+        match self.footnotes.as_ref() {
+            Some(footnotes) => footnotes.label(label),
+            None => Cow::from(label),
+        }
+    }
+
+    /// Write the formatted text of a footnote definition that comes after the `[^label]:`.
+    fn write_footnote_definition_body(
+        &mut self,
+        buffer: &str,
+        footnote_indent: Cow<'static, str>,
+    ) -> std::fmt::Result {
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let indentation = self.formatter.get_config(|c| c.footnote_indentation());
+        if matches!(indentation, FootnoteIndentation::TwoSpaces) && is_single_paragraph(buffer) {
+            // Continuation lines don't need to be indented by four spaces, since they're lazy
+            // continuation lines of the paragraph that starts on the label's line.
+            write!(self, " ")?;
+            self.indentation.push("  ".into());
+            self.join_with_indentation(buffer, false)?;
+        } else {
+            writeln!(self)?;
+            self.indentation.push(footnote_indent);
+            self.join_with_indentation(buffer, true)?;
+        }
+        self.indentation.pop();
+        Ok(())
+    }
+
+    /// Format a footnote definition on its own so that it can be written at the end of the
+    /// document.
+    fn defer_footnote_definition(
+        &mut self,
+        label: &str,
+        buffer: &str,
+        footnote_indent: Cow<'static, str>,
+    ) -> std::fmt::Result {
+        // Write into a new footnote buffer so the definition isn't indented like its parent
+        let indentation = std::mem::take(&mut self.indentation);
+        let footnote = FootnoteDefinition::new(vec![], buffer.len() * 2);
+        self.writers.push(footnote.into());
+
+        let new_label = self.footnote_label(label);
+        write!(self, "[^{new_label}]:")?;
+        self.write_footnote_definition_body(buffer, footnote_indent)?;
+
+        let Some(MarkdownWriter::FootnoteDefinition(f)) = self.writers.pop() else {
+            unreachable!("Should have popped a MarkdownWriter::FootnoteDefinition")
+        };
+        self.indentation = indentation;
+
+        let (definition, _, _) = f.into_parts();
+        let order = self
+            .footnotes
+            .as_ref()
+            .map_or(usize::MAX, |f| f.reference_order(label));
+        self.deferred_footnote_definitions.push((order, definition));
+        Ok(())
+    }
+
+    /// Write the deferred footnote definitions in the order that they're first referenced.
+    fn write_deferred_footnote_definitions(&mut self) -> std::fmt::Result {
+        let mut footnotes = std::mem::take(&mut self.deferred_footnote_definitions);
+        footnotes.sort_by_key(|(order, _)| *order);
+        for (_, definition) in footnotes {
+            // Separate each definition from the preceding block with a blank line
+            self.write_newlines(2)?;
+            self.write_str(&definition)?;
+            self.needs_indent = true;
+        }
+        Ok(())
     }
 
     fn join_with_indentation(
@@ -443,19 +971,29 @@ where
         Ok(())
     }
 
+    /// Remove the source indentation from a line that continues a multi-line event. Tabs in the
+    /// source indentation are expanded to the next multiple of 4 columns.
     fn trim_leading_indentation<'a>(&self, s: &'a str) -> &'a str {
         let mut output = s;
+        let mut column = 0;
+        let mut indentation_end = 0;
         for indent in self.indentation.iter() {
             if indent.starts_with('>') {
-                output = output.trim_start();
-                output = output
-                    .strip_prefix('>')
-                    .map(|o| o.strip_prefix(char::is_whitespace).unwrap_or(o))
-                    .unwrap_or(output);
-            } else {
-                for _ in 0..indent.len() {
-                    output = output.strip_prefix(char::is_whitespace).unwrap_or(output);
+                (output, column) = trim_start_columns(output, column, usize::MAX);
+                if let Some(rest) = output.strip_prefix('>') {
+                    output = rest;
+                    column += 1;
+                    // The optional space after the `>` could be part of a tab
+                    if let Some(rest) = output.strip_prefix(' ') {
+                        (output, column) = (rest, column + 1);
+                    } else if let Some(rest) = output.strip_prefix('\t') {
+                        (output, column) = (rest, next_tab_stop(column));
+                    }
                 }
+                indentation_end = column;
+            } else {
+                indentation_end += indent.len();
+                (output, column) = trim_start_columns(output, column, indentation_end);
             }
         }
         output
@@ -476,12 +1014,19 @@ where
             let line = if i == 0 {
                 // Don't need to trim leading space from the first line. The assumption is
                 // that the event would start after any leading indentation.
-                s
+                s.into()
             } else {
-                self.trim_leading_indentation(s)
+                let line = self.trim_leading_indentation(s);
+                // Tabs are part of a code span's content
+                let is_code = matches!(event, Event::Code(_));
+                if !is_code && self.formatter.get_config(|c| c.expand_leading_tabs()) {
+                    expand_leading_tabs(line, self.indentation_len())
+                } else {
+                    line.into()
+                }
             };
 
-            let trailing_space_count = count_trailing_spaces(line);
+            let trailing_space_count = count_trailing_spaces(&line);
             let trailing_spaces = get_spaces(trailing_space_count);
 
             let line = line.trim_end();
@@ -524,11 +1069,58 @@ where
             input,
             events: iter.peekable(),
             rewrite_buffer: String::with_capacity(input.len() * 2),
-            // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-            // list_markers: vec![],
+            list_markers: vec![],
+            loose_lists: vec![],
+            lists_with_blank_lines_in_items: formatter.get_config(|c| match c.list_spacing() {
+                ListSpacing::Tight => lists_with_blank_lines_in_items(input),
+                ListSpacing::Loose | ListSpacing::Preserve => HashSet::new(),
+            }),
+            source_loose_lists: if formatter.get_config(|c| c.blank_lines_around_blocks()) {
+                loose_lists(input)
+            } else {
+                HashSet::new()
+            },
+            tight_lists: vec![],
+            last_code_block_was_fenced: false,
+            wrote_list_separator: false,
             indentation: vec![],
             nested_context: vec![],
             reference_links: vec![],
+            blank_line_before_skipped_definitions: None,
+            link_labels: HashSet::new(),
+            used_link_labels: HashSet::new(),
+            link_definitions: vec![],
+            footnote_references: FootnoteReferences::default(),
+            footnote_definitions: vec![],
+            footnotes: formatter.get_config(|c| {
+                let moves_footnotes = !matches!(
+                    c.footnote_definition_placement(),
+                    FootnoteDefinitionPlacement::Preserve
+                );
+                (moves_footnotes
+                    || c.renumber_footnotes()
+                    || c.remove_unused_footnote_definitions())
+                .then(|| Footnotes::new(input, c.renumber_footnotes()))
+            }),
+            skipped_footnote_definition: None,
+            footnote_definitions_ending_lists: formatter.get_config(|c| {
+                let moves_footnotes = !matches!(
+                    c.footnote_definition_placement(),
+                    FootnoteDefinitionPlacement::Preserve
+                );
+                if moves_footnotes || c.remove_unused_footnote_definitions() {
+                    footnote_definitions_ending_lists(input)
+                } else {
+                    HashSet::new()
+                }
+            }),
+            deferred_footnote_definitions: vec![],
+            diagnostics: vec![],
+            link_references: LinkReferences::new(
+                input,
+                formatter.get_config(|c| c.link_style()),
+                formatter.get_config(|c| c.remove_unused_link_definitions()),
+            ),
             writers: vec![],
             needs_indent: false,
             last_position: 0,
@@ -575,13 +1167,83 @@ where
         rewrite
     }
 
+    /// Get the code fence for a fenced code block. Unless the source fence is preserved, this is
+    /// the shortest fence that's longer than any run of the fence character in the `code`.
+    ///
+    /// `source_fence` is `None` when rewriting an indented code block as a fenced code block.
+    fn code_fence(
+        &self,
+        source_fence: Option<&'i str>,
+        info_string: &str,
+        code: &str,
+    ) -> Cow<'i, str> {
+        let fence_char = match (
+            self.formatter.get_config(|c| c.code_fence_style()),
+            source_fence,
+        ) {
+            (CodeFenceStyle::Preserve, Some(source_fence)) => return source_fence.into(),
+            (CodeFenceStyle::Tilde, _) => '~',
+            // Backtick code fences can't have an info string that contains a backtick
+            (CodeFenceStyle::Backtick | CodeFenceStyle::Preserve, _)
+                if info_string.contains('`') =>
+            {
+                '~'
+            }
+            (CodeFenceStyle::Backtick | CodeFenceStyle::Preserve, _) => '`',
+        };
+
+        let longest_run = code
+            .split(|c| c != fence_char)
+            .map(str::len)
+            .max()
+            .unwrap_or_default();
+
+        fence_char
+            .to_string()
+            .repeat((longest_run + 1).max(3))
+            .into()
+    }
+
+    fn write_opening_code_fence(
+        &mut self,
+        code_fence: &str,
+        range: &Range<usize>,
+        info_string: &str,
+    ) -> std::fmt::Result {
+        self.write_str(code_fence)?;
+
+        if info_string.is_empty() {
+            return writeln!(self);
+        }
+
+        let marker_char = self.input[range.start..]
+            .chars()
+            .next()
+            .expect("should have found a ` or ~");
+
+        let starts_with_space = self.input[range.clone()]
+            .trim_start_matches(marker_char)
+            .starts_with(char::is_whitespace);
+
+        let info_string = self.input[range.clone()]
+            .lines()
+            .next()
+            .unwrap_or(info_string)
+            .trim_start_matches(marker_char)
+            .trim();
+
+        if starts_with_space {
+            writeln!(self, " {info_string}")
+        } else {
+            writeln!(self, "{info_string}")
+        }
+    }
+
     fn write_code_block_buffer(
         &mut self,
         info_string: Option<&str>,
-        unformatted_code: String,
+        code: String,
     ) -> std::fmt::Result {
-        let code = self.format_code_buffer(info_string, unformatted_code);
-
         if code.trim().is_empty() && info_string.is_some() {
             // The code fence is empty, and a newline should already ahve been added
             // when pushing the opening code fence, so just return.
@@ -598,8 +1260,9 @@ where
         Ok(())
     }
 
-    /// The main entry point for markdown formatting.
-    pub fn format(mut self) -> Result<String, std::fmt::Error> {
+    /// The main entry point for markdown formatting. Problems found in the input are returned
+    /// along with the formatted output.
+    pub fn format_with_diagnostics(mut self) -> Result<(String, Vec<Diagnostic>), std::fmt::Error> {
         while let Some((event, range)) = self.events.next() {
             tracing::debug!(?event, ?range, last_position = self.last_position);
             let mut last_position = if matches!(event, Event::HardBreak) {
@@ -725,7 +1388,6 @@ where
                                 || needs_escape.is_some()
                                 || could_be_interpreted_as_html(text, &mut self)
                                 || should_escape_an_escape(text, &mut self, range)
-                                || (self.in_table() && text.starts_with('|'))
                             {
                                 // recover escape characters
                                 write_context!(self, Escape, "\\{text}")?;
@@ -763,10 +1425,14 @@ where
                     }
                 }
                 Event::HardBreak => {
-                    let hard_break = match &self.input[range] {
-                        "\\\r" | "\\\r\n" | "\\\n" => "\\\n",
-                        "  \r" | "  \r\n" | "  \n" => "  \n",
-                        h => h,
+                    let hard_break = match self.formatter.get_config(|c| c.hard_break()) {
+                        HardBreak::Backslash => "\\\n",
+                        HardBreak::TwoSpaces => "  \n",
+                        HardBreak::Preserve => match &self.input[range] {
+                            "\\\r" | "\\\r\n" | "\\\n" => "\\\n",
+                            "  \r" | "  \r\n" | "  \n" => "  \n",
+                            h => h,
+                        },
                     };
 
                     self.write_event_str(&event, hard_break)?;
@@ -776,20 +1442,46 @@ where
                     if self.needs_indent {
                         self.write_newlines(newlines)?;
                     }
-                    let snippet = &self.input[range].trim_end();
-                    self.write_event_str(&event, snippet)?;
+                    let snippet = self.input[range].trim_end();
+                    let snippet = if self.formatter.get_config(|c| c.expand_leading_tabs()) {
+                        expand_leading_tabs(snippet, unicode_str_width(self.current_line()))
+                    } else {
+                        snippet.into()
+                    };
+                    self.write_event_str(&event, &snippet)?;
                     self.check_needs_indent(&event);
                 }
                 Event::Rule => {
                     let reference_definition_range = self.last_position..range.start;
                     self.rewrite_reference_link_definitions(&reference_definition_range)?;
-                    let newlines = self.count_newlines(&range);
+                    let rule = self.thematic_break(&range);
+                    let rule = rule.as_ref();
+
+                    // A `---` right after a paragraph would be parsed as a setext header
+                    let mut newlines = self.count_newlines_before_block(&range, false);
+                    let last_was_paragraph =
+                        matches!(self.last_event, Some((Event::End(TagEnd::Paragraph), _)));
+                    if newlines < 2 && last_was_paragraph && rule.chars().all(|c| c == '-') {
+                        newlines += 1;
+                    }
                     self.write_newlines(newlines)?;
-                    self.write_event_str(&event, self.input[range].trim_end())?;
+
+                    let rule_char = rule.chars().next().unwrap_or('-');
+                    if self.count_trailing_chars_on_current_line(rule_char) > 0 {
+                        // The thematic break starts on the same line as a list marker like `- `.
+                        // Use `_` so that the list item and thematic break don't get parsed as
+                        // a single thematic break.
+                        let rule = rule.replace(rule_char, "_");
+                        self.write_event_str(&event, &rule)?;
+                    } else {
+                        self.write_event_str(&event, rule)?;
+                    }
                     self.check_needs_indent(&event)
                 }
                 Event::FootnoteReference(ref text) => {
-                    write_context!(self, &event, "[^{text}]")?;
+                    self.footnote_references.add_reference(text);
+                    let label = self.footnote_label(text);
+                    write_context!(self, &event, "[^{label}]")?;
                 }
                 Event::TaskListMarker(done) => {
                     if done {
@@ -807,22 +1499,307 @@ where
         }
         debug_assert!(self.nested_context.is_empty());
 
-        let trailing_newline = self
-            .input
-            .rfind(|c: char| !c.is_whitespace())
-            .is_some_and(|start| self.input[start..].contains(['\r', '\n']));
+        let trailing_newline = self.formatter.get_config(|c| c.insert_final_newline())
+            || self
+                .input
+                .rfind(|c: char| !c.is_whitespace())
+                .is_some_and(|start| self.input[start..].contains(['\r', '\n']));
+
+        self.rewrite_final_reference_links()?;
+        let diagnostics = self.take_diagnostics();
+        let mut output = self.rewrite_buffer;
 
-        self.rewrite_final_reference_links().map(|mut output| {
-            // Prevent extranious newlines at the end of the output
-            while output.ends_with(['\r', '\n']) {
-                output.pop();
+        // Prevent extranious newlines at the end of the output
+        while output.ends_with(['\r', '\n']) {
+            output.pop();
+        }
+
+        if trailing_newline && !output.is_empty() {
+            output.push('\n');
+        }
+        Ok((output, diagnostics))
+    }
+
+    /// Keep track of the labels that links use so that problems with them can be reported.
+    fn record_link_reference(&mut self, link_type: LinkType, id: &str, range: &Range<usize>) {
+        match link_type {
+            LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut => {
+                self.used_link_labels.insert(normalize_link_label(id));
+            }
+            LinkType::ShortcutUnknown
+                if !self
+                    .formatter
+                    .get_config(|c| c.report_undefined_shortcut_references()) => {}
+            LinkType::ReferenceUnknown | LinkType::CollapsedUnknown | LinkType::ShortcutUnknown => {
+                let mut range = range.clone();
+                // The parser's range for collapsed links doesn't include the trailing `[]`
+                let is_collapsed = matches!(link_type, LinkType::CollapsedUnknown);
+                if is_collapsed && self.input[range.end..].starts_with("[]") {
+                    range.end += 2;
+                }
+                let kind = DiagnosticKind::UndefinedReference;
+                let diagnostic = Diagnostic::new(kind, id.to_owned(), range);
+                self.diagnostics.push(diagnostic);
             }
+            _ => {}
+        }
+    }
+
+    /// Get all the problems found in the input, ordered by where they're found.
+    fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = std::mem::take(&mut self.diagnostics);
+        let unused_definitions = std::mem::take(&mut self.link_definitions)
+            .into_iter()
+            .filter(|(label, _)| !self.used_link_labels.contains(label))
+            .map(|(_, diagnostic)| diagnostic);
+        diagnostics.extend(unused_definitions);
+        let footnotes = Footnotes::from_references(&self.footnote_references);
+        let unused_footnotes = std::mem::take(&mut self.footnote_definitions)
+            .into_iter()
+            .filter(|(label, _)| !footnotes.is_referenced(label))
+            .map(|(_, diagnostic)| diagnostic);
+        diagnostics.extend(unused_footnotes);
+        diagnostics.sort_by_key(|d| d.range().start);
+        diagnostics
+    }
 
-            if trailing_newline {
-                output.push('\n');
+    /// Determine which marker to write for the next list item based on the configured
+    /// [OrderedListNumbering].
+    fn next_list_marker(&mut self, source_list_marker: ListMarker) -> ListMarker {
+        let numbering = self.formatter.get_config(|c| c.ordered_list_numbering());
+        match self.list_markers.last_mut() {
+            Some(list_marker @ ListMarker::Unordered(_)) => list_marker.clone(),
+            Some(list_marker @ ListMarker::Ordered { .. }) => match numbering {
+                OrderedListNumbering::Preserve => {
+                    // Keep the item's number, but use the list's delimiter
+                    let mut next_list_marker = source_list_marker;
+                    next_list_marker.use_delimiter_of(list_marker);
+                    next_list_marker
+                }
+                OrderedListNumbering::One => list_marker.clone(),
+                OrderedListNumbering::Sequential => {
+                    let next_list_marker = list_marker.clone();
+                    list_marker.increment_count();
+                    next_list_marker
+                }
+            },
+            None => source_list_marker,
+        }
+    }
+
+    /// Apply the configured [BulletStyle] or [OrderedListDelimiter] to the marker of a list
+    /// that's about to start.
+    fn configured_list_marker(&self, source_list_marker: ListMarker) -> ListMarker {
+        let source_marker = match source_list_marker {
+            ListMarker::Unordered(source_marker) => source_marker,
+            ListMarker::Ordered {
+                zero_padding,
+                number,
+                marker,
+            } => {
+                let marker = match self.formatter.get_config(|c| c.ordered_list_delimiter()) {
+                    OrderedListDelimiter::Period => OrderedListMarker::Period,
+                    OrderedListDelimiter::Parenthesis => OrderedListMarker::Parenthesis,
+                    OrderedListDelimiter::Preserve => marker,
+                };
+                return ListMarker::Ordered {
+                    zero_padding,
+                    number,
+                    marker,
+                };
             }
-            output
-        })
+        };
+
+        let depth = self
+            .list_markers
+            .iter()
+            .filter(|m| matches!(m, ListMarker::Unordered(_)))
+            .count();
+
+        let marker = self.formatter.get_config(|c| match c.bullet_style() {
+            BulletStyle::Consistent(bullet) => bullet.into(),
+            BulletStyle::AlternateByDepth(bullets) if !bullets.is_empty() => {
+                (&bullets[depth % bullets.len()]).into()
+            }
+            BulletStyle::AlternateByDepth(_) | BulletStyle::Preserve => source_marker,
+        });
+        ListMarker::Unordered(marker)
+    }
+
+    /// Write the opening or closing delimiter for emphasis, strong emphasis, or strikethrough.
+    ///
+    /// The configured delimiter is only used when it can't change how the text is parsed.
+    /// Otherwise the delimiter from the source is kept.
+    fn rewrite_emphasis_marker(&mut self, range: &Range<usize>, tag: TagEnd) -> std::fmt::Result {
+        let (size_limit, configured_marker) = match tag {
+            TagEnd::Emphasis => {
+                let marker = match self.formatter.get_config(|c| c.emphasis_marker()) {
+                    EmphasisMarker::Asterisk => Some("*"),
+                    EmphasisMarker::Underscore => Some("_"),
+                    EmphasisMarker::Preserve => None,
+                };
+                (Some(1), marker)
+            }
+            TagEnd::Strong => {
+                let marker = match self.formatter.get_config(|c| c.strong_marker()) {
+                    EmphasisMarker::Asterisk => Some("**"),
+                    EmphasisMarker::Underscore => Some("__"),
+                    EmphasisMarker::Preserve => None,
+                };
+                (Some(2), marker)
+            }
+            TagEnd::Strikethrough => {
+                let marker = match self.formatter.get_config(|c| c.strikethrough_marker()) {
+                    StrikethroughMarker::SingleTilde => Some("~"),
+                    StrikethroughMarker::DoubleTilde => Some("~~"),
+                    StrikethroughMarker::Preserve => None,
+                };
+                (None, marker)
+            }
+            _ => unreachable!("only called for emphasis, strong, and strikethrough"),
+        };
+
+        // The text of collapsed and shortcut reference links is also the link label, which
+        // needs to match the link reference definition exactly.
+        let in_link_label = self.nested_context.iter().any(|tag| {
+            matches!(
+                tag,
+                Tag::Link { link_type, .. } | Tag::Image { link_type, .. } if matches!(
+                    link_type,
+                    LinkType::Collapsed
+                        | LinkType::CollapsedUnknown
+                        | LinkType::Shortcut
+                        | LinkType::ShortcutUnknown
+                )
+            )
+        });
+
+        let source_marker = find_marker_with_limit(self.input, range, size_limit);
+        match configured_marker {
+            _ if in_link_label => self.write_str(source_marker),
+            Some(marker)
+                if can_replace_emphasis_marker(self.input, range, source_marker, marker) =>
+            {
+                self.write_str(marker)
+            }
+            _ => self.write_str(source_marker),
+        }
+    }
+
+    /// Get the configured [ThematicBreak] text, or the text from the source.
+    fn thematic_break(&mut self, range: &Range<usize>) -> Cow<'i, str> {
+        let source_rule = self.input[range.clone()].trim_end();
+        let rule: Cow<'i, str> = self.formatter.get_config(|c| match c.thematic_break() {
+            ThematicBreak::Canonical(rule) if is_thematic_break(rule) => {
+                rule.trim().to_owned().into()
+            }
+            ThematicBreak::FillWidth(rule_char @ ('-' | '*' | '_')) => {
+                let width = c
+                    .max_width()
+                    .map(|w| w.saturating_sub(self.indentation_len()))
+                    .unwrap_or_default()
+                    .max(3);
+                rule_char.to_string().repeat(width).into()
+            }
+            _ => source_rule.into(),
+        });
+
+        if rule == "---" && self.could_delimit_metadata_block(range) {
+            return source_rule.into();
+        }
+
+        rule
+    }
+
+    /// Check if a `---` rule written for the thematic break at `range` could open or close a
+    /// metadata block.
+    ///
+    /// A `---` line that's directly followed by another line in the same block opens a metadata
+    /// block as long as a later `---` line closes it. That's true anywhere in the document, not
+    /// just at the start.
+    fn could_delimit_metadata_block(&mut self, range: &Range<usize>) -> bool {
+        // Block quote markers, list markers, and indentation come before the `---` in nested
+        // blocks
+        let is_blank = |line: &str| line.trim_start_matches([' ', '>']).trim().is_empty();
+        let is_opener = |line: &str, next_line: &str| {
+            let ends_with_rule = line
+                .trim_end()
+                .strip_suffix("---")
+                .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with([' ', '>']));
+            ends_with_rule && !is_blank(next_line)
+        };
+
+        // When the thematic break is the last thing in a list item or block quote, the next line
+        // starts a different block
+        let followed_by_line = !self.is_next_end_event()
+            && self.input[range.end..]
+                .lines()
+                .next()
+                .is_some_and(|line| !is_blank(line));
+
+        followed_by_line
+            || self
+                .rewrite_buffer
+                .lines()
+                .tuple_windows()
+                .any(|(line, next_line)| is_opener(line, next_line))
+    }
+
+    /// Nested lists and thematic breaks can start on the same line as a list item. Make sure we
+    /// don't write something like `- - -`, which would be parsed as a thematic break.
+    fn avoid_thematic_break(&self, list_marker: ListMarker, range: &Range<usize>) -> ListMarker {
+        let ListMarker::Unordered(marker) = list_marker else {
+            return list_marker;
+        };
+
+        let marker_char = char::from(&marker);
+        let leading_marker_count = self.count_trailing_chars_on_current_line(marker_char);
+
+        // Whatever comes after the list marker on the first line of the list
+        let rest_of_line = split_lines(&self.input[range.clone()])
+            .next()
+            .unwrap_or_default()
+            .trim_start()
+            .get(1..)
+            .unwrap_or_default();
+        let trailing_marker_count = if rest_of_line
+            .chars()
+            .all(|c| c == marker_char || c.is_whitespace())
+        {
+            rest_of_line.chars().filter(|c| *c == marker_char).count()
+        } else {
+            0
+        };
+
+        if leading_marker_count + 1 + trailing_marker_count < 3 {
+            return list_marker;
+        }
+
+        let alternative = [
+            UnorderedListMarker::Hyphen,
+            UnorderedListMarker::Asterisk,
+            UnorderedListMarker::Plus,
+        ]
+        .into_iter()
+        .find(|m| *m != marker)
+        .expect("there are always alternative markers");
+        ListMarker::Unordered(alternative)
+    }
+
+    /// Check if the list we just finished and the next list would be written with the same
+    /// marker. If so, they'd get merged into a single list on the next formatting run.
+    fn next_list_needs_separator(&mut self, list_marker: &ListMarker) -> bool {
+        let next_range = match self.events.peek() {
+            Some((Event::Start(Tag::List(_)), next_range)) => next_range.clone(),
+            _ => return false,
+        };
+
+        let Ok(next_list_marker) = ListMarker::from_str(&self.input[next_range]) else {
+            return false;
+        };
+
+        let next_list_marker = self.configured_list_marker(next_list_marker);
+        list_marker.marker_char() == next_list_marker.marker_char()
     }
 
     fn start_tag(&mut self, tag: Tag<'i>, range: Range<usize>) -> std::fmt::Result {
@@ -836,7 +1813,7 @@ where
         match tag {
             Tag::Paragraph => {
                 if self.needs_indent {
-                    let newlines = self.count_newlines(&range);
+                    let newlines = self.count_newlines_before_block(&range, false);
                     self.write_newlines(newlines)?;
                     self.needs_indent = false;
                 }
@@ -851,12 +1828,27 @@ where
                 self.writers.push(paragraph.into());
             }
             Tag::Heading { .. } => {
+                let mut newlines = self.count_newlines_before_block(&range, true);
+                let placement = self.formatter.get_config(|c| c.link_definition_placement());
+                // A top level heading starts a new section
+                if matches!(placement, LinkDefinitionPlacement::EndOfSection)
+                    && self.write_deferred_reference_link_definitions_before_block()?
+                {
+                    newlines = 2;
+                }
                 if self.needs_indent {
-                    let newlines = self.count_newlines(&range);
                     self.write_newlines(newlines)?;
                     self.needs_indent = false;
                 }
                 let full_header = self.input[range].trim();
+                let heading_style = match self.formatter.get_config(|c| c.heading_style()) {
+                    // ATX headers can directly follow a paragraph, but a setext header would
+                    // be parsed as part of the paragraph, so keep the ATX header.
+                    HeadingStyle::Setext if newlines == 1 && full_header.starts_with('#') => {
+                        HeadingStyle::Preserve
+                    }
+                    style => style,
+                };
                 let header = Header::new(
                     // Take the indentaiton so that we don't accidentally write indentation into the
                     // headers for setext headers that may span multiple lines.
@@ -864,6 +1856,8 @@ where
                     std::mem::take(&mut self.indentation),
                     full_header,
                     tag,
+                    heading_style,
+                    self.formatter.get_config(|c| c.close_atx_headings()),
                 );
                 self.writers.push(header.into())
             }
@@ -875,13 +1869,14 @@ where
                 // Just in case we're starting a new block quote in a nested context where
                 // We alternate indentation levels we want to remove trailing whitespace
                 // from the blockquote that we're about to push on top of
+                let nested_marker = self.nested_blockquote_marker();
                 if let Some(indent) = self.indentation.last_mut() {
                     if indent == "> " {
-                        *indent = ">".into()
+                        *indent = nested_marker.into()
                     }
                 }
 
-                let newlines = self.count_newlines(&range);
+                let newlines = self.count_newlines_before_block(&range, false);
                 if self.needs_indent {
                     self.write_newlines(newlines)?;
                     self.needs_indent = false;
@@ -899,10 +1894,11 @@ where
                     Some((Event::End(TagEnd::BlockQuote(..)), _)) => {
                         let snippet = &self.input[range.clone()];
                         let link_defs = parse_link_reference_definitions(snippet, range.start);
-                        if !link_defs.is_empty() {
+                        // Deferred or removed definitions are picked up by the End(BlockQuote) event
+                        if self.writes_reference_link_definitions(&link_defs) {
                             write!(self, "> ")?;
                             self.indentation.push("> ".into());
-                            self.rewrite_reference_link_definitions_inner(link_defs)?;
+                            self.rewrite_reference_link_definitions_inner(link_defs, true)?;
                             // remove trailing space in case we're about to push newlines
                             *self.indentation.last_mut().unwrap() = ">".into();
                         } else {
@@ -917,9 +1913,13 @@ where
                         let link_defs = parse_link_reference_definitions(snippet, range.start);
 
                         if link_defs.is_empty() {
-                            write!(self, ">")?;
-                            self.indentation.push(">".into());
-                            let newlines = count_newlines(snippet);
+                            let newlines = self.leading_newlines_in_block_quote(snippet);
+                            if newlines > 0 {
+                                write!(self, ">")?;
+                            } else {
+                                write!(self, "{nested_marker}")?;
+                            }
+                            self.indentation.push(nested_marker.into());
                             self.write_newlines(newlines)?;
                         } else {
                             let end = link_defs.first().expect("we have link_defs").range().start;
@@ -933,7 +1933,7 @@ where
                                 write!(self, "> ")?;
                             }
 
-                            self.rewrite_reference_link_definitions_inner(link_defs)?;
+                            self.rewrite_reference_link_definitions_inner(link_defs, true)?;
                         }
                     }
                     Some((_, next_range)) => {
@@ -945,7 +1945,11 @@ where
                             .map(|l| l.range().start)
                             .unwrap_or(next_range.start);
                         let newline_snippet = &self.input[self.last_position..end];
-                        let newlines = count_newlines(newline_snippet);
+                        let newlines = if link_defs.is_empty() {
+                            self.leading_newlines_in_block_quote(newline_snippet)
+                        } else {
+                            count_newlines(newline_snippet)
+                        };
 
                         self.indentation.push("> ".into());
                         if newlines > 0 {
@@ -955,7 +1959,7 @@ where
                         }
 
                         if !link_defs.is_empty() {
-                            self.rewrite_reference_link_definitions_inner(link_defs)?;
+                            self.rewrite_reference_link_definitions_inner(link_defs, true)?;
                         } else {
                             self.write_newlines(newlines)?;
                         }
@@ -967,7 +1971,10 @@ where
                 }
             }
             Tag::CodeBlock(kind) => {
-                let newlines = self.count_newlines(&range);
+                self.last_code_block_was_fenced =
+                    matches!(kind, CodeBlockKind::Fenced(_)) || self.fence_indented_code_blocks();
+                let newlines =
+                    self.count_newlines_before_block(&range, self.last_code_block_was_fenced);
                 if self.needs_indent && newlines > 0 {
                     self.write_newlines(newlines)?;
                     self.needs_indent = false;
@@ -975,40 +1982,16 @@ where
                 let capacity = (range.end - range.start) * 2;
                 let code_block_buffer = String::with_capacity(capacity);
                 match &kind {
-                    CodeBlockKind::Fenced(info_string) => {
-                        rewrite_marker(self.input, &range, self)?;
-
-                        if info_string.is_empty() {
-                            writeln!(self)?;
-                            let writer = MarkdownWriter::CodeBlock((code_block_buffer, kind));
-                            self.writers.push(writer);
-                            return Ok(());
-                        }
-
-                        let marker_char = self.input[range.start..]
-                            .chars()
-                            .next()
-                            .expect("should have found a ` or ~");
-
-                        let starts_with_space = self.input[range.clone()]
-                            .trim_start_matches(marker_char)
-                            .starts_with(char::is_whitespace);
-
-                        let info_string = self.input[range]
-                            .lines()
-                            .next()
-                            .unwrap_or_else(|| info_string.as_ref())
-                            .trim_start_matches(marker_char)
-                            .trim();
-
-                        if starts_with_space {
-                            writeln!(self, " {info_string}")?;
-                        } else {
-                            writeln!(self, "{info_string}")?;
-                        }
+                    CodeBlockKind::Fenced(_) => {
+                        // The opening code fence is written in `end_tag`, once we know what the
+                        // code looks like, so that we can pick a code fence that's long enough.
+                    }
+                    CodeBlockKind::Indented if self.fence_indented_code_blocks() => {
+                        // Written as a fenced code block in `end_tag`
                     }
                     CodeBlockKind::Indented => {
-                        // TODO(ytmimi) support tab as an indent
+                        // The parser turns the columns of a partially used tab into spaces, so the
+                        // code block content never relies on a tab for its 4 column indentation.
                         let indentation = "    ";
 
                         if !matches!(self.peek(), Some(Event::End(TagEnd::CodeBlock))) {
@@ -1024,49 +2007,41 @@ where
                 self.writers.push(writer);
             }
             Tag::List(_) => {
-                if self.needs_indent {
-                    // FIXME(ytmimi) The parser sometimes gets the ranges for a nested list
-                    // wrong if it's preceded by a tab (\t) character
-                    // See https://github.com/pulldown-cmark/pulldown-cmark/issues/983
-                    let snippet = &self.input[range.clone()];
-                    let special_case_list_start = snippet.starts_with(char::is_whitespace);
-
-                    // Extra check to keep the output idempotent when we've got a nested list.
-                    // Depending on how things get formated we might accidentally parse the
-                    // paragraph and list start as a Setext header
-                    let needs_extra_newline = |mut newlines: usize, range: Range<usize>| -> usize {
-                        let last_was_paragraph =
-                            matches!(self.last_event, Some((Event::End(TagEnd::Paragraph), _)));
-                        let starts_with_dash = self.input[range].starts_with('-');
-                        if newlines < 2 && last_was_paragraph && starts_with_dash {
-                            newlines += 1;
-                        }
-
-                        newlines
-                    };
-
-                    let newlines = if special_case_list_start {
-                        let start_offset = snippet.find(LIST_START_CHARS).unwrap_or(0);
-                        let marker_start = range.start + start_offset;
-                        let list_start_range = self.last_position..marker_start;
-                        let newlines = self.count_newlines_in_range(&list_start_range);
-                        needs_extra_newline(newlines, marker_start..range.end)
-                    } else {
-                        self.count_newlines(&range)
-                    };
+                let range = list_marker_start(self.input, &range)..range.end;
+                let source_list_marker = ListMarker::from_str(&self.input[range.clone()])
+                    .expect("Should be able to parse a list marker");
+                let list_marker = self.configured_list_marker(source_list_marker);
 
+                if self.needs_indent {
+                    let newlines = self.count_newlines_before_block(&range, false);
                     self.write_newlines(newlines)?;
                     self.needs_indent = false;
                 }
 
-                // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-                // let list_marker = ListMarker::from_str(&self.input[range])
-                //    .expect("Should be able to parse a list marker");
-                // self.list_markers.push(list_marker);
+                let list_marker = self.avoid_thematic_break(list_marker, &range);
+                self.list_markers.push(list_marker);
+                let loose_list = match self.formatter.get_config(|c| c.list_spacing()) {
+                    ListSpacing::Tight => {
+                        Some(self.lists_with_blank_lines_in_items.contains(&range.start))
+                    }
+                    ListSpacing::Loose => Some(true),
+                    ListSpacing::Preserve => None,
+                };
+                let is_tight = match loose_list {
+                    Some(loose) => !loose,
+                    None => !self.source_loose_lists.contains(&range.start),
+                };
+                self.tight_lists.push(is_tight);
+                self.loose_lists.push(loose_list);
                 self.nested_context.push(tag);
             }
             Tag::Item => {
-                let newlines = self.count_newlines(&range);
+                let range = list_marker_start(self.input, &range)..range.end;
+                let first_item = matches!(self.last_event, Some((Event::Start(Tag::List(_)), _)));
+                let newlines = match self.loose_lists.last() {
+                    Some(Some(loose)) if !first_item => 1 + usize::from(*loose),
+                    _ => self.count_newlines(&range),
+                };
                 if self.needs_indent && newlines > 0 {
                     self.write_newlines(newlines)?;
                 }
@@ -1086,7 +2061,7 @@ where
                     count_newlines(snippet) > 0
                 };
 
-                let list_marker = ListMarker::from_str(&self.input[range.clone()])
+                let source_list_marker = ListMarker::from_str(&self.input[range.clone()])
                     .expect("Should be able to parse a list marker");
 
                 // FIXME(ytmimi) luckily recovering link-reference-definitions isn't overly
@@ -1095,8 +2070,10 @@ where
                 let (empty_list_item, link_defs) = match self.events.peek() {
                     Some((Event::End(TagEnd::Item), _)) => {
                         let snippet = &self.input[range.clone()];
-                        let just_list_marker = snippet.trim().len() == list_marker.len();
                         let link_defs = parse_link_reference_definitions(snippet, range.start);
+                        // Deferred or removed definitions leave nothing behind in the list item
+                        let just_list_marker = snippet.trim().len() == source_list_marker.len()
+                            || !self.writes_reference_link_definitions(&link_defs);
                         let end = link_defs
                             .first()
                             .map(|l| l.range().start)
@@ -1124,43 +2101,58 @@ where
                 // this is an empty list item
                 self.needs_indent = empty_list_item;
 
-                // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-                // Take list_marker so we can use `write!(self, ...)`
-                // let mut list_marker = self
-                //     .list_markers
-                //     .pop()
-                //     .expect("can't have list item without marker");
+                let list_marker = self.next_list_marker(source_list_marker);
                 let marker_char = list_marker.marker_char();
+                let indentation = list_marker.indentation(self.list_indent(empty_list_item));
                 match &list_marker {
-                    ListMarker::Ordered { number, .. } if empty_list_item => {
-                        let zero_padding = list_marker.zero_padding();
-                        write!(self, "{zero_padding}{number}{marker_char}")?;
-                    }
                     ListMarker::Ordered { number, .. } => {
                         let zero_padding = list_marker.zero_padding();
-                        write!(self, "{zero_padding}{number}{marker_char} ")?;
-                    }
-                    ListMarker::Unordered(_) if empty_list_item => {
-                        write!(self, "{marker_char}")?;
+                        write!(self, "{zero_padding}{number}{marker_char}")?;
                     }
                     ListMarker::Unordered(_) => {
-                        write!(self, "{marker_char} ")?;
+                        write!(self, "{marker_char}")?;
                     }
                 }
+                if !empty_list_item {
+                    write!(self, "{}", &indentation[list_marker.len()..])?;
+                }
 
                 self.nested_context.push(tag);
-                // Increment the list marker in case this is a ordered list and
-                // swap the list marker we took earlier
-                self.indentation.push(list_marker.indentation());
-                // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-                // list_marker.increment_count();
-                // self.list_markers.push(list_marker)
+                self.indentation.push(indentation);
 
-                self.rewrite_reference_link_definitions_inner(link_defs)?;
+                self.rewrite_reference_link_definitions_inner(link_defs, true)?;
             }
             Tag::FootnoteDefinition(ref label) => {
+                let kind = DiagnosticKind::UnusedFootnoteDefinition;
+                let end = range.start + self.input[range.clone()].trim_end().len();
+                let diagnostic = Diagnostic::new(kind, label.to_string(), range.start..end);
+                self.footnote_definitions
+                    .push((label.to_string(), diagnostic));
+                self.footnote_references.start_definition(label);
+
                 let newlines = self.count_newlines(&range);
-                self.write_newlines(newlines)?;
+                let (placement, remove_unused) = self.formatter.get_config(|c| {
+                    let placement = c.footnote_definition_placement();
+                    (placement, c.remove_unused_footnote_definitions())
+                });
+                let is_referenced = self
+                    .footnotes
+                    .as_ref()
+                    .map_or(true, |f| f.is_referenced(label));
+                // A blank line before a definition at the end of a list's last item could be the
+                // only reason that the list is loose, so the definition needs to stay in the list.
+                let can_move = newlines < 2
+                    || !self
+                        .footnote_definitions_ending_lists
+                        .contains(&range.start);
+                let remove = can_move && remove_unused && !is_referenced;
+                let defer =
+                    can_move && matches!(placement, FootnoteDefinitionPlacement::EndOfDocument);
+                if remove || defer {
+                    self.skipped_footnote_definition = Some((label.to_string(), !remove, newlines));
+                } else {
+                    self.write_newlines(newlines)?;
+                }
 
                 // Anchor the last_position at the start of the footnote definition. This prevents
                 // picking up extra newlines in case we need to recover refernce link definitions
@@ -1187,7 +2179,10 @@ where
                     }
                 };
 
-                write!(self, "[^{label}]:")?;
+                if self.skipped_footnote_definition.is_none() {
+                    let label = self.footnote_label(label);
+                    write!(self, "[^{label}]:")?;
+                }
 
                 let footnote = FootnoteDefinition::new(
                     // Take the indentaiton so that nested items are written without indentation.
@@ -1197,18 +2192,24 @@ where
                 );
 
                 self.writers.push(footnote.into());
-                self.rewrite_reference_link_definitions_inner(link_defs)?;
+                self.rewrite_reference_link_definitions_inner(link_defs, true)?;
             }
             Tag::Emphasis => {
-                rewrite_marker_with_limit(self.input, &range, self, Some(1))?;
+                self.rewrite_emphasis_marker(&range, TagEnd::Emphasis)?;
             }
             Tag::Strong => {
-                rewrite_marker_with_limit(self.input, &range, self, Some(2))?;
+                self.rewrite_emphasis_marker(&range, TagEnd::Strong)?;
             }
             Tag::Strikethrough => {
-                rewrite_marker(self.input, &range, self)?;
+                self.rewrite_emphasis_marker(&range, TagEnd::Strikethrough)?;
+            }
+            Tag::Link {
+                link_type, ref id, ..
             }
-            Tag::Link { link_type, .. } | Tag::Image { link_type, .. } => {
+            | Tag::Image {
+                link_type, ref id, ..
+            } => {
+                self.record_link_reference(link_type, id, &range);
                 let newlines = self.count_newlines(&range);
                 if self.needs_indent && newlines > 0 {
                     self.write_newlines(newlines)?;
@@ -1224,7 +2225,7 @@ where
             }
             Tag::Table(ref alignment) => {
                 if self.needs_indent {
-                    let newlines = self.count_newlines(&range);
+                    let newlines = self.count_newlines_before_block(&range, true);
                     self.write_newlines(newlines)?;
                     self.needs_indent = false;
                 }
@@ -1247,13 +2248,30 @@ where
                     return Ok(());
                 }
 
+                // Cells that are missing from the end of a row are empty, and start where the
+                // row ends
+                let is_missing = range.is_empty()
+                    && (range.start == self.input.len()
+                        || self.input[..range.start].ends_with(['\n', '\r']));
+                if is_missing && !self.formatter.get_config(|c| c.add_missing_table_cells()) {
+                    return Ok(());
+                }
+
                 if let Some(MarkdownWriter::Table(state)) = self.writers.last_mut() {
                     state.write(String::new().into());
                 }
             }
             Tag::HtmlBlock => {
+                let mut newlines = self.count_newlines_before_block(&range, true);
+                // Deferred link reference definitions written after an HTML block that never
+                // ends would become part of the HTML block, so write them out before it.
+                if self.input[range.end..].trim().is_empty()
+                    && is_unterminated_html_block(&self.input[range.clone()])
+                    && self.write_deferred_reference_link_definitions_before_block()?
+                {
+                    newlines = 2;
+                }
                 if self.needs_indent {
-                    let newlines = self.count_newlines(&range);
                     self.write_newlines(newlines)?;
                 }
 
@@ -1299,8 +2317,7 @@ where
                 let Some(MarkdownWriter::Header(h)) = self.writers.pop() else {
                     unreachable!("Should have popped a MarkdownWriter::Header")
                 };
-                let header_kind = h.kind();
-                let (buffer, indentation) = h.into_parts()?;
+                let (header_kind, buffer, indentation) = h.into_parts()?;
 
                 if let HeaderKind::Atx(level) = header_kind {
                     let atx_header = match level {
@@ -1376,14 +2393,32 @@ where
 
                 match kind {
                     CodeBlockKind::Fenced(info_string) => {
-                        self.write_code_block_buffer(Some(info_string.as_ref()), code_block)?;
+                        let code = self.format_code_buffer(Some(info_string.as_ref()), code_block);
+                        let source_fence = find_marker_with_limit(self.input, &range, None);
+                        let code_fence = self.code_fence(Some(source_fence), &info_string, &code);
+                        self.write_opening_code_fence(&code_fence, &range, &info_string)?;
+                        self.write_code_block_buffer(Some(info_string.as_ref()), code)?;
+                        // write closing code fence
+                        self.write_indentation(false)?;
+                        self.write_str(&code_fence)?;
+                    }
+                    CodeBlockKind::Indented if self.fence_indented_code_blocks() => {
+                        let info_string = self
+                            .formatter
+                            .get_config(|c| c.fence_indented_code_blocks().map(str::to_owned))
+                            .unwrap_or_default();
+                        let code = self.format_code_buffer(Some(&info_string), code_block);
+                        let code_fence = self.code_fence(None, &info_string, &code);
+                        writeln!(self, "{code_fence}{info_string}")?;
+                        self.write_code_block_buffer(Some(&info_string), code)?;
                         // write closing code fence
                         self.write_indentation(false)?;
-                        rewrite_marker(self.input, &range, self)?;
+                        self.write_str(&code_fence)?;
                     }
                     CodeBlockKind::Indented => {
                         // Maybe we'll consider formatting indented code blocks??
-                        self.write_code_block_buffer(None, code_block)?;
+                        let code = self.format_code_buffer(None, code_block);
+                        self.write_code_block_buffer(None, code)?;
 
                         let popped_indentation = self
                             .indentation
@@ -1396,8 +2431,25 @@ where
             TagEnd::List(_) => {
                 let popped_tag = self.nested_context.pop();
                 debug_assert_eq!(popped_tag.map(|t| t.to_end()), Some(tag));
-                // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-                // self.list_markers.pop();
+                let popped_list_marker = self
+                    .list_markers
+                    .pop()
+                    .expect("we pushed a list marker in start_tag");
+                self.loose_lists.pop();
+                self.tight_lists.pop();
+
+                // Adjacent lists that use different markers are separate lists. If we're about
+                // to write both lists with the same marker we'll need to separate them.
+                // The HTML comments are HTML blocks, which might need blank lines around them
+                let blank_lines_around_blocks =
+                    self.formatter.get_config(|c| c.blank_lines_around_blocks());
+                let separator_newlines = 1 + usize::from(blank_lines_around_blocks);
+
+                if self.next_list_needs_separator(&popped_list_marker) {
+                    self.write_newlines(separator_newlines)?;
+                    write!(self, "<!-- Keep adjacent lists separate -->")?;
+                    self.wrote_list_separator = blank_lines_around_blocks;
+                }
 
                 // To prevent the next code block from being interpreted as a list we'll add an
                 // HTML comment See https://spec.commonmark.org/0.30/#example-308, which states:
@@ -1405,16 +2457,38 @@ where
                 //     To separate consecutive lists of the same type, or to separate a list from an
                 //     indented code block that would otherwise be parsed as a subparagraph of the
                 //     final list item, you can insert a blank HTML comment
-                if let Some(Event::Start(Tag::CodeBlock(CodeBlockKind::Indented))) = self.peek() {
-                    self.write_newlines(1)?;
-                    writeln!(self, "<!-- Don't absorb code block into list -->")?;
+                //
+                // Fenced code blocks don't have this problem.
+                let next_is_indented_code_block = matches!(
+                    self.peek(),
+                    Some(Event::Start(Tag::CodeBlock(CodeBlockKind::Indented)))
+                );
+                if next_is_indented_code_block && !self.fence_indented_code_blocks() {
+                    self.write_newlines(separator_newlines)?;
+                    write!(self, "<!-- Don't absorb code block into list -->")?;
+                    if blank_lines_around_blocks {
+                        self.write_newlines(2)?;
+                    } else {
+                        writeln!(self)?;
+                    }
                     write!(self, "<!-- Consider a fenced code block instead -->")?;
                 };
             }
             TagEnd::Item => {
-                let ref_def_range = self.last_position..range.end;
-                self.rewrite_reference_link_definitions(&ref_def_range)?;
-                let newlines = self.count_newlines(&range);
+                let snippet = &self.input[self.last_position..range.end];
+                let link_defs = parse_link_reference_definitions(snippet, self.last_position);
+                // A blank line before definitions at the end of the last item could be the only
+                // reason that the list is loose, so those definitions need to stay in the list.
+                let is_last_item = matches!(self.peek(), Some(Event::End(TagEnd::List(_))));
+                self.rewrite_reference_link_definitions_inner(link_defs, !is_last_item)?;
+                // The next list item writes the configured spacing between list items
+                let spacing_is_configured = matches!(self.loose_lists.last(), Some(Some(_)));
+                let next_is_item = matches!(self.peek(), Some(Event::Start(Tag::Item)));
+                let newlines = if spacing_is_configured && next_is_item {
+                    0
+                } else {
+                    self.count_newlines(&range)
+                };
                 if self.needs_indent && newlines > 0 {
                     self.write_newlines_no_trailing_whitespace(newlines)?;
                 }
@@ -1441,27 +2515,29 @@ where
 
                 let (buffer, indentation, footnote_indent) = f.into_parts();
                 self.indentation = indentation;
-
-                if !buffer.is_empty() {
-                    writeln!(self)?;
-                    self.indentation.push(footnote_indent);
-                    self.join_with_indentation(&buffer, true)?;
-                    self.indentation.pop();
+                self.footnote_references.end_definition();
+
+                if let Some((label, keep, newlines)) = self.skipped_footnote_definition.take() {
+                    let end = self.input[..range.end]
+                        .trim_end_matches(|c: char| c.is_ascii_whitespace())
+                        .len();
+                    self.blank_line_before_skipped_definitions = (newlines > 1).then_some(end);
+                    self.trim_end_spaces_on_current_line();
+                    if keep {
+                        self.defer_footnote_definition(&label, &buffer, footnote_indent)?;
+                    }
+                    return Ok(());
                 }
 
+                self.write_footnote_definition_body(&buffer, footnote_indent)?;
+
                 if let Some(Event::Start(Tag::FootnoteDefinition(_))) = self.peek() {
                     // separte consecutive footnote definitinons by at least one line
                     self.write_newlines(1)?;
                 };
             }
-            TagEnd::Emphasis => {
-                rewrite_marker_with_limit(self.input, &range, self, Some(1))?;
-            }
-            TagEnd::Strong => {
-                rewrite_marker_with_limit(self.input, &range, self, Some(2))?;
-            }
-            TagEnd::Strikethrough => {
-                rewrite_marker(self.input, &range, self)?;
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.rewrite_emphasis_marker(&range, tag)?;
             }
             TagEnd::Link | TagEnd::Image => {
                 debug_assert!(matches!(self.writers.last(), Some(MarkdownWriter::Link(_))));
@@ -1473,29 +2549,29 @@ where
                 let popped_tag = self.nested_context.pop();
                 debug_assert_eq!(popped_tag.as_ref().map(|t| t.to_end()), Some(tag));
 
-                let (link_type, url, title) = match popped_tag {
+                let (link_type, url, title, id) = match popped_tag {
                     Some(
                         ref tag @ Tag::Link {
                             ref link_type,
                             ref dest_url,
                             ref title,
-                            ..
+                            ref id,
                         },
                     ) => {
                         let email_or_auto =
                             matches!(link_type, LinkType::Email | LinkType::Autolink);
                         let opener = if email_or_auto { "<" } else { "[" };
                         self.write_tag_str(tag, opener)?;
-                        (link_type, dest_url, title)
+                        (link_type, dest_url, title, id)
                     }
                     Some(Tag::Image {
                         ref link_type,
                         ref dest_url,
                         ref title,
-                        ..
+                        ref id,
                     }) => {
                         write!(self, "![")?;
-                        (link_type, dest_url, title)
+                        (link_type, dest_url, title, id)
                     }
                     _ => {
                         panic!("Expected a Tag::Link or Tag::Image")
@@ -1504,46 +2580,87 @@ where
                 self.write_str(&link_writer.into_buffer())?;
                 let text = &self.input[range.clone()];
 
-                match link_type {
-                    LinkType::Inline => {
-                        if let Some((source_url, title_and_quote)) =
-                            crate::links::find_inline_url_and_title(text)
-                        {
-                            self.write_inline_link(&source_url, title_and_quote)?;
-                        } else {
-                            let title = if title.is_empty() {
-                                None
-                            } else {
-                                Some((title, '"'))
-                            };
-                            self.write_inline_link(url, title)?;
-                        }
+                let inline_destination_and_title = match link_type {
+                    LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut => self
+                        .link_references
+                        .as_ref()
+                        .and_then(|r| r.inline_destination_and_title(id)),
+                    _ => None,
+                };
+                let write_as_reference = matches!(link_type, LinkType::Inline)
+                    && matches!(
+                        self.formatter.get_config(|c| c.link_style()),
+                        LinkStyle::Reference
+                    );
+
+                if let Some((url, title)) = inline_destination_and_title {
+                    self.write_inline_link(&url, title)?;
+                } else if write_as_reference {
+                    let (source_url, source_title) = crate::links::find_inline_url_and_title(text)
+                        .unwrap_or_else(|| {
+                            let title = (!title.is_empty()).then(|| (title.to_string(), '"'));
+                            (url.to_string(), title)
+                        });
+                    let link_references = self
+                        .link_references
+                        .as_mut()
+                        .expect("link references are tracked when links are rewritten");
+                    let (label, link_def) = link_references.reference_label(
+                        url,
+                        title,
+                        &source_url,
+                        source_title,
+                        range,
+                    );
+                    write!(self, "][{label}]")?;
+                    if let Some(link_def) = link_def {
+                        let link_def = self.normalize_reference_link_definition(link_def);
+                        self.reference_links.push(link_def);
                     }
-                    LinkType::Reference | LinkType::ReferenceUnknown => {
-                        let label = crate::links::find_reference_link_label(text);
-                        if count_newlines(label) > 0 {
-                            write!(self, "][")?;
-                            let label = split_lines(label)
-                                .map(|l| self.trim_leading_indentation(l))
-                                .join("\n");
-
-                            if label.starts_with('^') {
-                                write!(self, "\\")?;
+                } else {
+                    match link_type {
+                        LinkType::Inline => {
+                            if let Some((source_url, title_and_quote)) =
+                                crate::links::find_inline_url_and_title(text)
+                            {
+                                self.write_inline_link(&source_url, title_and_quote)?;
+                            } else {
+                                let title = if title.is_empty() {
+                                    None
+                                } else {
+                                    Some((title, '"'))
+                                };
+                                self.write_inline_link(url, title)?;
                             }
+                        }
+                        LinkType::Reference | LinkType::ReferenceUnknown => {
+                            let label = crate::links::find_reference_link_label(text);
+                            if count_newlines(label) > 0 {
+                                write!(self, "][")?;
+                                let label = split_lines(label)
+                                    .map(|l| self.trim_leading_indentation(l))
+                                    .join("\n");
+
+                                if label.starts_with('^') {
+                                    write!(self, "\\")?;
+                                }
 
-                            self.write_str(&label)?;
-                            write!(self, "]")?;
-                        } else if label.starts_with('^') {
-                            write!(self, "][\\{label}]")?;
-                        } else {
-                            write!(self, "][{label}]")?;
+                                self.write_str(&label)?;
+                                write!(self, "]")?;
+                            } else if label.starts_with('^') {
+                                write!(self, "][\\{label}]")?;
+                            } else {
+                                write!(self, "][{label}]")?;
+                            }
+                        }
+                        LinkType::Collapsed | LinkType::CollapsedUnknown => write!(self, "][]")?,
+                        LinkType::Shortcut | LinkType::ShortcutUnknown => write!(self, "]")?,
+                        LinkType::Autolink | LinkType::Email => write!(self, ">")?,
+                        LinkType::WikiLink { .. } => {
+                            unreachable!(
+                                "pulldown_cmark::Options::ENABLE_WIKILINKS is not configured"
+                            )
                         }
-                    }
-                    LinkType::Collapsed | LinkType::CollapsedUnknown => write!(self, "][]")?,
-                    LinkType::Shortcut | LinkType::ShortcutUnknown => write!(self, "]")?,
-                    LinkType::Autolink | LinkType::Email => write!(self, ">")?,
-                    LinkType::WikiLink { .. } => {
-                        unreachable!("pulldown_cmark::Options::ENABLE_WIKILINKS is not configured")
                     }
                 }
             }
@@ -1556,7 +2673,15 @@ where
                 let Some(MarkdownWriter::Table(t)) = self.writers.pop() else {
                     unreachable!("Should have popped a MarkdownWriter::Table")
                 };
-                self.join_with_indentation(&t.format()?, false)?;
+                let (style, align_cells, max_width) = self.formatter.get_config(|c| {
+                    // The indentation includes the leading `|` of each line
+                    let width = c
+                        .max_width()
+                        .map(|w| w.saturating_sub(self.indentation_len()));
+                    (c.table_style(), c.align_table_cells(), width)
+                });
+                let table = t.format(style, align_cells, max_width)?;
+                self.join_with_indentation(&table, false)?;
                 let popped_indentation = self.indentation.pop().expect("we added `|` in start_tag");
                 debug_assert_eq!(popped_indentation, "|");
             }
@@ -1609,19 +2734,56 @@ where
 }
 
 /// Find some marker, but limit the size
-fn rewrite_marker_with_limit<W: std::fmt::Write>(
-    input: &str,
+fn find_marker_with_limit<'i>(
+    input: &'i str,
     range: &Range<usize>,
-    writer: &mut W,
     size_limit: Option<usize>,
-) -> std::fmt::Result {
+) -> &'i str {
     let marker_char = input[range.start..].chars().next().unwrap();
     let marker = find_marker(input, range, |c| c != marker_char);
     if let Some(mark_max_width) = size_limit {
-        writer.write_str(&marker[..mark_max_width])
+        &marker[..mark_max_width]
     } else {
-        writer.write_str(marker)
+        marker
+    }
+}
+
+/// Check if the emphasis, strong emphasis, or strikethrough delimiters in `range` can be
+/// replaced with `new_marker` without changing how the text is parsed.
+///
+/// This is intentionally conservative. For example, `_` can't be used within a word
+/// like `snake*case*`, and a new delimiter shouldn't merge with a neighboring delimiter run.
+fn can_replace_emphasis_marker(
+    input: &str,
+    range: &Range<usize>,
+    marker: &str,
+    new_marker: &str,
+) -> bool {
+    if marker == new_marker {
+        return true;
+    }
+
+    let Some(new_marker_char) = new_marker.chars().next() else {
+        return false;
+    };
+
+    // Avoid creating new delimiter runs that could open or close emphasis within the content.
+    let content_start = range.start + marker.len();
+    let content_end = range.end.saturating_sub(marker.len());
+    match input.get(content_start..content_end) {
+        Some(content) if !content.contains(new_marker_char) => {}
+        _ => return false,
     }
+
+    let is_safe_neighbor = |c: Option<char>| match c {
+        None => true,
+        Some('*' | '_' | '~') => false,
+        Some(c) if new_marker_char == '_' => c.is_whitespace() || c.is_ascii_punctuation(),
+        Some(_) => true,
+    };
+
+    is_safe_neighbor(input[..range.start].chars().next_back())
+        && is_safe_neighbor(input[range.end..].chars().next())
 }
 
 /// Finds a marker in the source text and writes it to the buffer
@@ -1630,5 +2792,5 @@ fn rewrite_marker<W: std::fmt::Write>(
     range: &Range<usize>,
     writer: &mut W,
 ) -> std::fmt::Result {
-    rewrite_marker_with_limit(input, range, writer, None)
+    writer.write_str(find_marker_with_limit(input, range, None))
 }
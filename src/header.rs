@@ -1,5 +1,6 @@
+use crate::config::HeadingStyle;
 use crate::escape::needs_escape;
-use crate::utils::sequence_ends_on_escape;
+use crate::utils::{sequence_ends_on_escape, unicode_str_width};
 use crate::writer::{MarkdownContext, WriteContext};
 use pulldown_cmark::{CowStr, Event, HeadingLevel, Tag};
 use std::borrow::Cow;
@@ -11,6 +12,8 @@ pub(super) struct Header<'i> {
     buffer: String,
     indentation: Vec<Cow<'static, str>>,
     kind: HeaderKind<'i>,
+    style: HeadingStyle,
+    close_atx_header: bool,
     attrs_on_own_line: bool,
     id: Option<CowStr<'i>>,
     classes: Vec<CowStr<'i>>,
@@ -60,6 +63,8 @@ impl<'i> Header<'i> {
         indentation: Vec<Cow<'static, str>>,
         full_header: &'i str,
         tag: Tag<'i>,
+        style: HeadingStyle,
+        close_atx_header: bool,
     ) -> Self {
         let Tag::Heading {
             level,
@@ -97,9 +102,14 @@ impl<'i> Header<'i> {
             .map(|l| l.trim_start_matches(|c: char| c.is_whitespace() || c == '>'))
             .any(|l| l.starts_with('{'));
 
+        let can_rewrite_as_setext =
+            style == HeadingStyle::Setext && atx_header_can_be_setext(full_header);
+
         let kind = match (level, header_marker) {
             (HeadingLevel::H1, Some(marker)) => HeaderKind::SetextH1(marker),
             (HeadingLevel::H2, Some(marker)) => HeaderKind::SetextH2(marker),
+            (HeadingLevel::H1, None) if can_rewrite_as_setext => HeaderKind::SetextH1("="),
+            (HeadingLevel::H2, None) if can_rewrite_as_setext => HeaderKind::SetextH2("-"),
             _ => HeaderKind::Atx(level),
         };
 
@@ -107,6 +117,8 @@ impl<'i> Header<'i> {
             buffer: String::with_capacity(full_header.len() * 2),
             indentation,
             kind,
+            style,
+            close_atx_header,
             attrs_on_own_line,
             id,
             classes,
@@ -130,11 +142,24 @@ impl<'i> Header<'i> {
     }
 
     /// Consume `self` and return the buffer along with any indentaion that we took
-    /// when creating `Self`.
+    /// when creating `Self`. The [HeaderKind] is returned as well since the final kind
+    /// might depend on the content of the header.
     pub(super) fn into_parts(
         mut self,
-    ) -> Result<(String, Vec<Cow<'static, str>>), std::fmt::Error> {
-        if matches!(self.kind(), HeaderKind::Atx(_)) && self.buffer.trim_end().ends_with('#') {
+    ) -> Result<(HeaderKind<'i>, String, Vec<Cow<'static, str>>), std::fmt::Error> {
+        self.rewrite_single_line_setext_as_atx();
+
+        // Empty headers are left open. Something like `# #` looks odd.
+        let write_closing_sequence = self.close_atx_header
+            && matches!(self.kind, HeaderKind::Atx(_))
+            && !self.buffer.trim().is_empty();
+
+        // A closing sequence makes sure trailing `#` are parsed as part of the header's content,
+        // so they don't need to be removed or escaped.
+        if matches!(self.kind(), HeaderKind::Atx(_))
+            && self.buffer.trim_end().ends_with('#')
+            && !write_closing_sequence
+        {
             if self.has_attributes() {
                 // Make sure we properly escape trailing `#` at the end of the header.
                 // Otherwise our formatting might not be idempotent
@@ -146,9 +171,38 @@ impl<'i> Header<'i> {
         }
 
         self.escape_trailing_empty_attribute_brackets();
+        if write_closing_sequence {
+            self.write_atx_closing_sequence()?;
+        }
         self.write_header_attributes()?;
         self.write_setext_header()?;
-        Ok((self.buffer, self.indentation))
+        Ok((self.kind, self.buffer, self.indentation))
+    }
+
+    /// When configured to use ATX headers, switch setext headers that only span a single line
+    /// over to ATX headers. Multi-line setext headers can't be written as ATX headers.
+    fn rewrite_single_line_setext_as_atx(&mut self) {
+        if self.style != HeadingStyle::Atx {
+            return;
+        }
+
+        let level = match self.kind {
+            HeaderKind::SetextH1(_) => HeadingLevel::H1,
+            HeaderKind::SetextH2(_) => HeadingLevel::H2,
+            HeaderKind::Atx(_) => return,
+        };
+
+        // Any newline would be a soft break or a hard break between lines of the header.
+        // Attributes written on their own line are removed from the content by the parser.
+        if self.buffer.trim_end().contains(['\r', '\n']) {
+            return;
+        }
+
+        while self.buffer.ends_with(char::is_whitespace) {
+            self.buffer.pop();
+        }
+        self.kind = HeaderKind::Atx(level);
+        self.attrs_on_own_line = false;
     }
 
     fn remove_trailing_hashtags(&mut self) {
@@ -274,7 +328,20 @@ impl<'i> Header<'i> {
                 }
 
                 writeln!(self.buffer)?;
-                writeln!(self.buffer, "{s}")?;
+
+                if self.style == HeadingStyle::Setext {
+                    // Underline the last line of the header
+                    let marker = if matches!(self.kind, HeaderKind::SetextH1(_)) {
+                        "="
+                    } else {
+                        "-"
+                    };
+                    let last_line = self.buffer.trim_end().rsplit(['\r', '\n']).next();
+                    let width = last_line.map(unicode_str_width).unwrap_or_default().max(3);
+                    writeln!(self.buffer, "{}", marker.repeat(width))?;
+                } else {
+                    writeln!(self.buffer, "{s}")?;
+                }
             }
             _ => {}
         }
@@ -282,6 +349,15 @@ impl<'i> Header<'i> {
         Ok(())
     }
 
+    /// Write the optional closing sequence for ATX headers. For example, `## Header ##`.
+    /// Header attributes are written after the closing sequence.
+    fn write_atx_closing_sequence(&mut self) -> std::fmt::Result {
+        if let HeaderKind::Atx(level) = self.kind {
+            write!(self.buffer, " {}", "#".repeat(level as usize))?;
+        }
+        Ok(())
+    }
+
     /// Write out formatted custom header attributes
     fn write_header_attributes(&mut self) -> std::fmt::Result {
         if self.id.is_none() && self.classes.is_empty() && self.attrs.is_empty() {
@@ -345,6 +421,24 @@ pub(super) enum HeaderKind<'i> {
     SetextH2(&'i str),
 }
 
+/// Check if the content of an ATX header can be underlined to turn it into a setext header
+/// without the content being parsed as some other markdown construct.
+///
+/// This is intentionally conservative, and rules out any content that starts with a character
+/// that could begin another block, like `-`, `>`, `1.`, or `<div>`.
+fn atx_header_can_be_setext(full_header: &str) -> bool {
+    let content = full_header.trim_start_matches('#').trim();
+    let Some(first_char) = content.chars().next() else {
+        return false;
+    };
+
+    !first_char.is_ascii_digit()
+        && !matches!(
+            first_char,
+            '#' | '>' | '-' | '*' | '+' | '_' | '=' | '~' | '`' | '<' | '{' | '|'
+        )
+}
+
 /// Rewrite custom header classes
 ///
 /// ```markdown
@@ -1,3 +1,5 @@
+use pulldown_cmark::{Event, Parser, Tag};
+
 pub(crate) static HTML_BLOCK_TAG: &[&str] = &[
     "article",
     "header",
@@ -94,3 +96,12 @@ pub(crate) fn starts_with_html_block_identifier(s: &str) -> bool {
         || html_block_condition_4(maybe_html)
         || html_block_condition_5(maybe_html)
 }
+
+/// Check if an HTML block would swallow any block that comes after it.
+///
+/// HTML blocks that start with `<!--`, `<pre>`, `<script>`, etc. only end once their closing
+/// condition is met, so they continue to the end of the document when it's missing.
+pub(crate) fn is_unterminated_html_block(html: &str) -> bool {
+    let input = format!("{}\n\nparagraph", html.trim_end());
+    !Parser::new(&input).any(|event| matches!(event, Event::Start(Tag::Paragraph)))
+}
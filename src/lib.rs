@@ -68,6 +68,7 @@
 mod adapters;
 mod builder;
 mod config;
+mod diagnostics;
 mod escape;
 mod footnote;
 mod formatter;
@@ -83,6 +84,13 @@ mod utils;
 mod writer;
 
 pub use builder::{CodeBlockContext, FormatBuilder};
+pub use config::{
+    BlockQuoteStyle, Bullet, BulletStyle, CodeFenceStyle, EmphasisMarker,
+    FootnoteDefinitionPlacement, FootnoteIndentation, HardBreak, HeadingStyle, LineEnding,
+    LinkDefinitionPlacement, LinkStyle, LinkTitleStyle, ListIndent, ListSpacing,
+    OrderedListDelimiter, OrderedListNumbering, StrikethroughMarker, TableStyle, ThematicBreak,
+};
+pub use diagnostics::{Diagnostic, DiagnosticKind};
 pub use formatter::MarkdownFormatter;
 
 // Used for doctests in the README
@@ -1,8 +1,11 @@
 use super::formatter::FormatState;
+use crate::config::LinkStyle;
 use crate::utils::{count_newlines, is_char_esacped, sequence_ends_on_escape, split_lines};
 use crate::writer::{MarkdownContext, WriteContext};
-use pulldown_cmark::Event;
+use itertools::Itertools;
+use pulldown_cmark::{Event, LinkType, Parser, Tag};
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
 /// Rewrites the content of all [LinkType](pulldown_cmark::LinkType) Events.
@@ -179,7 +182,7 @@ fn find_text_within_last_set_of_balance_bracket(
                 }
             }
         }
-        was_last_escape = byte == b'\\'
+        was_last_escape = !was_last_escape && byte == b'\\'
     }
     (start, end + 1)
 }
@@ -342,6 +345,243 @@ impl<'a> LinkReferenceDefinitionBuilder<'a> {
     }
 }
 
+/// Normalize a link label so that it can be compared with other labels. Labels match
+/// case-insensitively once consecutive whitespace is collapsed into a single space.
+///
+/// See <https://spec.commonmark.org/0.31.2/#matches>
+pub(crate) fn normalize_link_label(label: &str) -> String {
+    // Going through uppercase folds characters like `ẞ` and `ß` to `ss`, so they match the way
+    // they do with Unicode case folding
+    let label = label.split_whitespace().join(" ");
+    label.to_lowercase().to_uppercase().to_lowercase()
+}
+
+/// Keeps track of the link reference definitions that are needed to convert links from one
+/// [LinkStyle] to another, and of the definitions that no link uses.
+pub(crate) struct LinkReferences<'i> {
+    /// Definitions used by reference links that are written as inline links.
+    /// Keyed by the normalized label of the reference links.
+    inline_definitions: HashMap<String, LinkReferenceDefinition<'i>>,
+    /// Normalized labels of the definitions that are no longer used once links are inlined.
+    inlined_labels: HashSet<String>,
+    /// Normalized labels of the definitions that aren't used by any link.
+    unused_labels: HashSet<String>,
+    /// Labels to use for inline links that are written as reference links.
+    /// Keyed by the link destination and title.
+    reference_labels: HashMap<(String, String), String>,
+    /// Normalized labels that are already defined or that are used by links.
+    taken_labels: HashSet<String>,
+    /// The number used for the last generated label.
+    last_generated_label: usize,
+}
+
+impl<'i> LinkReferences<'i> {
+    /// Find the link reference definitions needed to write links with the given [LinkStyle].
+    /// Returns `None` if links keep the style they had in the source and unused definitions
+    /// don't need to be removed.
+    pub(crate) fn new(input: &'i str, style: LinkStyle, remove_unused: bool) -> Option<Self> {
+        if matches!(style, LinkStyle::Preserve) && !remove_unused {
+            return None;
+        }
+
+        let mut callback = |_| Some(("".into(), "".into()));
+        let options = crate::pulldown_cmark_options!();
+        let parser = Parser::new_with_broken_link_callback(input, options, Some(&mut callback));
+
+        // Sort definitions by where they're defined so that we pick labels deterministically
+        let mut definitions = parser
+            .reference_definitions()
+            .iter()
+            .map(|(label, def)| {
+                let title = def.title.as_deref().unwrap_or_default();
+                let target = (def.dest.to_string(), title.to_owned());
+                (label.split_whitespace().join(" "), target, def.span.clone())
+            })
+            .collect::<Vec<_>>();
+        definitions.sort_by_key(|(_, _, span)| span.start);
+
+        let mut link_references = LinkReferences {
+            inline_definitions: HashMap::new(),
+            inlined_labels: HashSet::new(),
+            unused_labels: HashSet::new(),
+            reference_labels: HashMap::new(),
+            taken_labels: HashSet::new(),
+            last_generated_label: 0,
+        };
+
+        for (label, target, _) in definitions.iter() {
+            link_references
+                .taken_labels
+                .insert(normalize_link_label(label));
+            // Labels starting with `^` are always written with a leading escape
+            if !label.starts_with('^') {
+                link_references
+                    .reference_labels
+                    .entry(target.clone())
+                    .or_insert_with(|| label.clone());
+            }
+        }
+
+        // Without the callback, brackets around undefined labels don't form links, which can
+        // change what the surrounding brackets mean. Only inline links that are parsed the same
+        // way either way.
+        let link_ranges = Parser::new_ext(input, options)
+            .into_offset_iter()
+            .filter_map(|(event, range)| match event {
+                Event::Start(Tag::Link { .. } | Tag::Image { .. }) => Some(range),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let mut ambiguous_labels = HashSet::new();
+        let mut used_labels = HashSet::new();
+
+        for (event, range) in parser.into_offset_iter() {
+            let (Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })
+            | Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })) = event
+            else {
+                continue;
+            };
+
+            let label = normalize_link_label(&id);
+            match link_type {
+                LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut => {
+                    used_labels.insert(label.clone());
+                }
+                // Inline links reuse existing definitions when they're written as reference links
+                LinkType::Inline if matches!(style, LinkStyle::Reference) => {
+                    let target = (dest_url.to_string(), title.to_string());
+                    if let Some(label) = link_references.reference_labels.get(&target) {
+                        used_labels.insert(normalize_link_label(label));
+                    }
+                }
+                _ => {}
+            }
+
+            match link_type {
+                LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut
+                    if matches!(style, LinkStyle::Inline) =>
+                {
+                    if !link_ranges.contains(&range) {
+                        ambiguous_labels.insert(label);
+                        continue;
+                    }
+                    if link_references.inline_definitions.contains_key(&label) {
+                        continue;
+                    }
+                    let Some((_, _, span)) = definitions
+                        .iter()
+                        .find(|(l, _, _)| normalize_link_label(l) == label)
+                    else {
+                        continue;
+                    };
+                    let snippet = &input[span.clone()];
+                    let Some(link_def) = parse_link_reference_definitions(snippet, span.start)
+                        .into_iter()
+                        .next()
+                    else {
+                        continue;
+                    };
+                    link_references
+                        .inlined_labels
+                        .insert(link_def.normalized_label());
+                    link_references.inline_definitions.insert(label, link_def);
+                }
+                LinkType::ReferenceUnknown
+                | LinkType::CollapsedUnknown
+                | LinkType::ShortcutUnknown => {
+                    // Defining these labels would turn plain text into links
+                    link_references.taken_labels.insert(label);
+                }
+                _ => {}
+            }
+        }
+
+        if remove_unused {
+            link_references.unused_labels = definitions
+                .iter()
+                .map(|(label, _, _)| normalize_link_label(label))
+                .filter(|label| !used_labels.contains(label))
+                .collect();
+        }
+
+        for label in ambiguous_labels {
+            if let Some(link_def) = link_references.inline_definitions.remove(&label) {
+                link_references
+                    .inlined_labels
+                    .remove(&link_def.normalized_label());
+            }
+        }
+
+        Some(link_references)
+    }
+
+    /// Get the destination and title that a reference link should use when it's written as an
+    /// inline link. The destination doesn't include the angle brackets it might've had in the
+    /// definition, since they're added back when the inline link is written if they're needed.
+    pub(crate) fn inline_destination_and_title(
+        &self,
+        id: &str,
+    ) -> Option<(String, Option<(String, char)>)> {
+        let link_def = self.inline_definitions.get(&normalize_link_label(id))?;
+        let url = match &link_def.destination.0 {
+            LinkDestination::Bracketed(url) | LinkDestination::Regular(url) => url.to_string(),
+        };
+        let title = link_def.title.as_ref().map(|title| {
+            let mut value = String::new();
+            let _ = title.value.write(&mut value);
+            (value, title.kind.closer())
+        });
+        Some((url, title))
+    }
+
+    /// Check if a definition is no longer used, either because all the links that used it are
+    /// inline, or because no link used it to begin with.
+    pub(crate) fn is_unused(&self, normalized_label: &str) -> bool {
+        self.inlined_labels.contains(normalized_label)
+            || self.unused_labels.contains(normalized_label)
+    }
+
+    /// Get the label that an inline link should use when it's written as a reference link.
+    ///
+    /// Links with the same destination and title share a label. A new definition is returned
+    /// when the label hasn't been defined yet.
+    pub(crate) fn reference_label(
+        &mut self,
+        dest_url: &str,
+        title: &str,
+        source_url: &str,
+        source_title: Option<(String, char)>,
+        range: std::ops::Range<usize>,
+    ) -> (String, Option<LinkReferenceDefinition<'i>>) {
+        let target = (dest_url.to_owned(), title.to_owned());
+        if let Some(label) = self.reference_labels.get(&target) {
+            return (label.clone(), None);
+        }
+
+        let label = loop {
+            self.last_generated_label += 1;
+            let label = self.last_generated_label.to_string();
+            if self.taken_labels.insert(label.clone()) {
+                break label;
+            }
+        };
+        self.reference_labels.insert(target, label.clone());
+
+        let link_def = LinkReferenceDefinition::new(&label, source_url, source_title, range);
+        (label, Some(link_def))
+    }
+}
+
 /// [Link Reference Definition]
 ///
 /// For example, here's a reference defintion.
@@ -357,6 +597,36 @@ pub(super) struct LinkReferenceDefinition<'a> {
 }
 
 impl LinkReferenceDefinition<'_> {
+    /// Create a definition for an inline link that's written as a reference link.
+    fn new(
+        label: &str,
+        url: &str,
+        title: Option<(String, char)>,
+        range: std::ops::Range<usize>,
+    ) -> Self {
+        let label = LinkLines::from((Cow::from(label.to_owned()), range.clone()));
+        let destination = match format_link_url(url, true) {
+            Cow::Owned(url) if url.starts_with('<') && url.ends_with('>') => {
+                LinkDestination::Bracketed(Cow::from(url[1..url.len() - 1].to_owned()))
+            }
+            url => LinkDestination::Regular(Cow::from(url.into_owned())),
+        };
+        let title = title.map(|(title, quote)| {
+            let kind = match quote {
+                '\'' => TitleMarker::SingleQuote,
+                ')' => TitleMarker::Parentheses,
+                _ => TitleMarker::DoubleQuote,
+            };
+            LinkTitle::new(kind, (Cow::from(title), range.clone()))
+        });
+
+        Self {
+            label,
+            destination: (destination, range),
+            title,
+        }
+    }
+
     pub(super) fn range(&self) -> std::ops::Range<usize> {
         let start = self.label.range().expect("we have a label").start;
         let end = if let Some(title) = self.title.as_ref() {
@@ -367,6 +637,46 @@ impl LinkReferenceDefinition<'_> {
         start..end
     }
 
+    /// The range of the whole definition, from the label's opening `[` to the end of the title,
+    /// or to the end of the destination if there's no title.
+    pub(super) fn span(&self) -> std::ops::Range<usize> {
+        // -1 to include the label's opening `[`
+        let start = self.label.range().expect("we have a label").start - 1;
+        // +1 to include closing `>` and title markers, which are all ascii
+        let end = match (&self.title, &self.destination) {
+            (Some(title), _) => title.range().expect("we have a title").end + 1,
+            (None, (LinkDestination::Bracketed(_), range)) => range.end + 1,
+            (None, (LinkDestination::Regular(_), range)) => range.end,
+        };
+        start..end
+    }
+
+    /// The label as it was written, with consecutive whitespace collapsed into a single space.
+    pub(super) fn label(&self) -> String {
+        self.label.iter().flat_map(str::split_whitespace).join(" ")
+    }
+
+    /// The label that links are matched against. Labels match case-insensitively once
+    /// consecutive whitespace is collapsed into a single space.
+    ///
+    /// See <https://spec.commonmark.org/0.31.2/#matches>
+    pub(super) fn normalized_label(&self) -> String {
+        normalize_link_label(&self.label.iter().join(" "))
+    }
+
+    /// Rewrite the label in lowercase, with consecutive whitespace collapsed into a single space.
+    pub(super) fn normalize_label(&mut self) {
+        let range = self.label.range().expect("we have a label");
+        self.label = LinkLines::from((Cow::from(self.label().to_lowercase()), range));
+    }
+
+    /// Change the quotes used to wrap the title. Does nothing if there isn't a title.
+    pub(super) fn set_title_marker(&mut self, marker: TitleMarker) {
+        if let Some(title) = self.title.as_mut() {
+            title.set_marker(marker);
+        }
+    }
+
     pub(super) fn write<W: std::fmt::Write>(&self, writer: &mut W) -> std::fmt::Result {
         write!(writer, "[")?;
         self.label.write(writer)?;
@@ -426,6 +736,28 @@ impl<'a> LinkTitle<'a> {
         self.value.range()
     }
 
+    /// Change the quotes used to wrap the title, escaping any characters that would otherwise
+    /// end the title early.
+    fn set_marker(&mut self, marker: TitleMarker) {
+        if self.kind == marker {
+            return;
+        }
+
+        for (text, _) in self.value.0.iter_mut() {
+            let mut escaped = String::with_capacity(text.len());
+            let mut was_last_escape = false;
+            for c in text.chars() {
+                if !was_last_escape && (c == marker.opener() || c == marker.closer()) {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+                was_last_escape = is_char_esacped(c, was_last_escape);
+            }
+            *text = Cow::from(escaped);
+        }
+        self.kind = marker;
+    }
+
     fn write<W: std::fmt::Write>(&self, writer: &mut W) -> std::fmt::Result {
         write!(writer, "{}", self.kind.opener())?;
         self.value.write(writer)?;
@@ -694,8 +1026,9 @@ fn parse_link_reference_definition<'i>(
                 // It's possible that the link is a single character
                 let is_last = iter.peek().is_none();
                 if is_last {
-                    let url = Cow::from(&input[idx..=idx]);
-                    builder.set_url(LinkDestination::Regular(url), idx..idx, offset);
+                    let end = idx + c.len_utf8();
+                    let url = Cow::from(&input[idx..end]);
+                    builder.set_url(LinkDestination::Regular(url), idx..end, offset);
                     parsed_until = idx;
                     break;
                 }
@@ -728,8 +1061,9 @@ fn parse_link_reference_definition<'i>(
                         if !c.is_whitespace() {
                             let is_last = iter.peek().is_none();
                             if is_last {
-                                let url = Cow::from(&input[start..=idx]);
-                                builder.set_url(LinkDestination::Regular(url), start..idx, offset);
+                                let end = idx + c.len_utf8();
+                                let url = Cow::from(&input[start..end]);
+                                builder.set_url(LinkDestination::Regular(url), start..end, offset);
                                 parsed_until = idx;
                                 break;
                             }
@@ -1196,4 +1530,79 @@ title
         ];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn normalize_link_reference_definition_label() {
+        let definition = "[  Foo\n   BAR  baz ]: /url";
+        let mut result = parse_link_reference_definitions(definition, 0);
+        let link_def = result.first_mut().unwrap();
+        assert_eq!(link_def.normalized_label(), "foo bar baz");
+
+        link_def.normalize_label();
+        let mut output = String::new();
+        link_def.write(&mut output).unwrap();
+        assert_eq!(output, "[foo bar baz]: /url");
+    }
+
+    #[test]
+    fn change_link_reference_definition_title_marker() {
+        let check = |definition: &str, marker: TitleMarker, expected: &str| {
+            let mut result = parse_link_reference_definitions(definition, 0);
+            let link_def = result.first_mut().unwrap();
+            link_def.set_title_marker(marker);
+            let mut output = String::new();
+            link_def.write(&mut output).unwrap();
+            assert_eq!(output, expected);
+        };
+
+        check(
+            r#"[a]: /a 'a "b"'"#,
+            TitleMarker::DoubleQuote,
+            r#"[a]: /a "a \"b\"""#,
+        );
+        check(
+            r#"[a]: /a "a \"b\"""#,
+            TitleMarker::SingleQuote,
+            r#"[a]: /a 'a \"b\"'"#,
+        );
+        check(
+            r#"[a]: /a "it's""#,
+            TitleMarker::SingleQuote,
+            r#"[a]: /a 'it\'s'"#,
+        );
+        check(
+            r#"[a]: /a "(a) \(b\)""#,
+            TitleMarker::Parentheses,
+            r#"[a]: /a (\(a\) \(b\))"#,
+        );
+        check(
+            r#"[a]: /a (title)"#,
+            TitleMarker::Parentheses,
+            r#"[a]: /a (title)"#,
+        );
+        check(r#"[a]: /a"#, TitleMarker::SingleQuote, r#"[a]: /a"#);
+    }
+
+    #[test]
+    fn escaped_backslash_before_closing_bracket() {
+        assert_eq!(find_reference_link_label(r"[foo\\][bar\\]"), r"bar\\");
+        assert_eq!(
+            find_inline_url_and_title(r"[foo\\](/url)"),
+            Some(("/url".to_owned(), None))
+        );
+    }
+
+    #[test]
+    fn link_destination_ends_with_multi_byte_char() {
+        check_parsed_link_reference_definition! {
+            definition: "[a]: é",
+            label: "a",
+            url: LinkDestination::Regular("é".into()),
+        }
+        check_parsed_link_reference_definition! {
+            definition: "[a]: /café",
+            label: "a",
+            url: LinkDestination::Regular("/café".into()),
+        }
+    }
 }
@@ -1,5 +1,10 @@
+use crate::config::{Bullet, ListIndent};
+use crate::utils::split_lines;
+use pulldown_cmark::{Event, Parser, Tag};
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::num::ParseIntError;
+use std::ops::Range;
 // Including all these spaces might be overkill, but it probably doesn't hurt.
 // In practice we'll see far fewer digits in an ordered list.
 //
@@ -15,7 +20,7 @@ pub(crate) static LIST_START_CHARS: &[char] = &[
     '*', '+', '-', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
 ];
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub(super) enum ListMarker {
     Ordered {
         zero_padding: usize,
@@ -32,19 +37,51 @@ impl std::default::Default for ListMarker {
 }
 
 impl ListMarker {
-    // TODO(ytmimi) Add a configuration to allow incrementing ordered lists
-    #[allow(dead_code)]
+    /// Increment the number of an ordered list marker.
+    ///
+    /// Zero padding is treated as part of the marker's width, so `09.` is followed by `10.`.
     pub(super) fn increment_count(&mut self) {
         match self {
-            Self::Ordered { number, .. } => {
+            Self::Ordered {
+                zero_padding,
+                number,
+                ..
+            } => {
+                let digits_before = number.checked_ilog10().unwrap_or(0);
                 *number += 1;
+                let digits_after = number.checked_ilog10().unwrap_or(0);
+                if digits_after > digits_before {
+                    *zero_padding = zero_padding.saturating_sub(1);
+                }
             }
             Self::Unordered(_) => {}
         }
     }
 
-    pub(super) fn indentation(&self) -> Cow<'static, str> {
-        let indent_index = self.indentation_len();
+    /// Use the same ordered list delimiter as `other`. For example, `.` or `)`.
+    pub(super) fn use_delimiter_of(&mut self, other: &ListMarker) {
+        if let (
+            Self::Ordered { marker, .. },
+            Self::Ordered {
+                marker: other_marker,
+                ..
+            },
+        ) = (self, other)
+        {
+            *marker = other_marker.clone();
+        }
+    }
+
+    /// The indentation for the content of a list item that starts with this marker.
+    pub(super) fn indentation(&self, list_indent: ListIndent) -> Cow<'static, str> {
+        // Rounding up to the next multiple of at most 4 never adds more than 4 spaces after the
+        // marker, which is the most that CommonMark allows before the content is parsed as an
+        // indented code block.
+        let indent_index = match list_indent {
+            ListIndent::MarkerWidth => self.indentation_len(),
+            ListIndent::TwoSpaces => self.indentation_len().next_multiple_of(2),
+            ListIndent::FourSpaces => self.indentation_len().next_multiple_of(4),
+        };
 
         if indent_index <= LIST_INDENTATION.len() {
             Cow::from(&LIST_INDENTATION[..indent_index])
@@ -136,6 +173,16 @@ impl From<&UnorderedListMarker> for char {
     }
 }
 
+impl From<&Bullet> for UnorderedListMarker {
+    fn from(value: &Bullet) -> Self {
+        match value {
+            Bullet::Asterisk => UnorderedListMarker::Asterisk,
+            Bullet::Plus => UnorderedListMarker::Plus,
+            Bullet::Hyphen => UnorderedListMarker::Hyphen,
+        }
+    }
+}
+
 impl TryFrom<char> for UnorderedListMarker {
     type Error = InvalidMarker;
 
@@ -206,6 +253,180 @@ impl std::str::FromStr for ListMarker {
     }
 }
 
+/// Find where the list marker of a list or list item starts.
+///
+/// FIXME(ytmimi) The parser sometimes gets the ranges for a nested list or list item wrong if
+/// it's preceded by a tab (\t) character, so the range can start before the list marker.
+/// See https://github.com/pulldown-cmark/pulldown-cmark/issues/983
+pub(super) fn list_marker_start(input: &str, range: &Range<usize>) -> usize {
+    let offset = input[range.clone()].find(LIST_START_CHARS).unwrap_or(0);
+    range.start + offset
+}
+
+/// What kind of element each [Event::Start] opened while looking through the lists in the input.
+enum OpenElement {
+    List { start: usize },
+    Item { previous_block_end: Option<usize> },
+    Block,
+    Inline,
+}
+
+/// Find the lists where a list item directly contains two blocks that are separated by a blank
+/// line. For example, a list item with two paragraphs. Lists are identified by where they start
+/// in the `input`.
+///
+/// Those lists are always loose, even when there aren't any blank lines between their items.
+pub(super) fn lists_with_blank_lines_in_items(input: &str) -> HashSet<usize> {
+    let mut lists = HashSet::new();
+    let mut open_elements: Vec<OpenElement> = vec![];
+
+    let mut check_block_in_item = |open_elements: &mut Vec<OpenElement>, start: usize| {
+        let [
+            ..,
+            OpenElement::List { start: list_start },
+            OpenElement::Item { previous_block_end },
+        ] = open_elements.as_slice()
+        else {
+            return;
+        };
+        if let Some(end) = previous_block_end {
+            if has_blank_line(input, *end..start) {
+                lists.insert(*list_start);
+            }
+        }
+    };
+
+    let parser = Parser::new_ext(input, crate::pulldown_cmark_options!()).into_offset_iter();
+    for (event, range) in parser {
+        match event {
+            Event::Start(Tag::List(_)) => {
+                let start = list_marker_start(input, &range);
+                check_block_in_item(&mut open_elements, start);
+                open_elements.push(OpenElement::List { start });
+            }
+            Event::Start(Tag::Item) => open_elements.push(OpenElement::Item {
+                previous_block_end: None,
+            }),
+            Event::Start(
+                Tag::Paragraph
+                | Tag::Heading { .. }
+                | Tag::BlockQuote(_)
+                | Tag::CodeBlock(_)
+                | Tag::HtmlBlock
+                | Tag::Table(_)
+                | Tag::FootnoteDefinition(_)
+                | Tag::DefinitionList,
+            ) => {
+                check_block_in_item(&mut open_elements, range.start);
+                open_elements.push(OpenElement::Block);
+            }
+            Event::Start(_) => open_elements.push(OpenElement::Inline),
+            Event::End(_) => {
+                let closed = open_elements.pop();
+                if let (
+                    Some(OpenElement::List { .. } | OpenElement::Block),
+                    Some(OpenElement::Item { previous_block_end }),
+                ) = (closed, open_elements.last_mut())
+                {
+                    *previous_block_end = Some(range.end);
+                }
+            }
+            Event::Rule => {
+                check_block_in_item(&mut open_elements, range.start);
+                if let Some(OpenElement::Item { previous_block_end }) = open_elements.last_mut() {
+                    *previous_block_end = Some(range.end);
+                }
+            }
+            _ => {}
+        }
+    }
+    lists
+}
+
+/// Find the lists in the `input` that are loose. Lists are identified by where they start in the
+/// `input`.
+pub(super) fn loose_lists(input: &str) -> HashSet<usize> {
+    let mut lists = HashSet::new();
+    let mut open_elements: Vec<OpenElement> = vec![];
+
+    let parser = Parser::new_ext(input, crate::pulldown_cmark_options!()).into_offset_iter();
+    for (event, range) in parser {
+        match event {
+            Event::Start(Tag::List(_)) => {
+                let start = list_marker_start(input, &range);
+                open_elements.push(OpenElement::List { start });
+            }
+            Event::Start(Tag::Item) => open_elements.push(OpenElement::Item {
+                previous_block_end: None,
+            }),
+            Event::Start(Tag::Paragraph) => {
+                // The text of a tight list's items isn't wrapped in paragraphs
+                if let [.., OpenElement::List { start }, OpenElement::Item { .. }] =
+                    open_elements.as_slice()
+                {
+                    lists.insert(*start);
+                }
+                open_elements.push(OpenElement::Block);
+            }
+            Event::Start(_) => open_elements.push(OpenElement::Block),
+            Event::End(_) => {
+                open_elements.pop();
+            }
+            _ => {}
+        }
+    }
+    lists
+}
+
+/// Find the footnote definitions that are the last block in the last item of a list. Footnote
+/// definitions are identified by where they start in the `input`.
+///
+/// A blank line before one of those definitions could be the only reason that the list is loose.
+pub(super) fn footnote_definitions_ending_lists(input: &str) -> HashSet<usize> {
+    let mut definitions = HashSet::new();
+    let mut open_elements: Vec<(Tag<'_>, Range<usize>)> = vec![];
+
+    let parser = Parser::new_ext(input, crate::pulldown_cmark_options!()).into_offset_iter();
+    for (event, range) in parser {
+        match event {
+            Event::Start(tag) => {
+                if let (Tag::FootnoteDefinition(_), [.., (Tag::List(_), list), (Tag::Item, item)]) =
+                    (&tag, open_elements.as_slice())
+                {
+                    if range.end == item.end && item.end == list.end {
+                        definitions.insert(range.start);
+                    }
+                }
+                open_elements.push((tag, range));
+            }
+            Event::End(_) => {
+                open_elements.pop();
+            }
+            _ => {}
+        }
+    }
+    definitions
+}
+
+/// Check if there's a blank line between two blocks. Lines that only contain the markers of
+/// block quotes that both blocks are nested in also count as blank lines.
+fn has_blank_line(input: &str, range: Range<usize>) -> bool {
+    // The first line is the rest of the previous block's last line, unless the previous block's
+    // range already included the newline.
+    let starts_on_new_line = input[..range.start].ends_with(['\n', '\r']);
+    // The last line is the indentation before the next block.
+    let lines = split_lines(&input[range]).collect::<Vec<_>>();
+    let full_lines = match lines.split_last() {
+        Some((_, full_lines)) => full_lines,
+        None => return false,
+    };
+    let skip = usize::from(!starts_on_new_line);
+    full_lines
+        .iter()
+        .skip(skip)
+        .any(|line| line.chars().all(|c| c.is_whitespace() || c == '>'))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -258,4 +479,21 @@ mod test {
         check_ordered_list!("003.", number = 3, padding = 2, marker = Period);
         check_ordered_list!("003)", number = 3, padding = 2, marker = Parenthesis);
     }
+
+    #[test]
+    fn increment_ordered_lists() {
+        let mut marker = ListMarker::from_str("8.").unwrap();
+        marker.increment_count();
+        assert_eq!(marker, ListMarker::from_str("9.").unwrap());
+        marker.increment_count();
+        assert_eq!(marker, ListMarker::from_str("10.").unwrap());
+
+        let mut marker = ListMarker::from_str("009)").unwrap();
+        marker.increment_count();
+        assert_eq!(marker, ListMarker::from_str("010)").unwrap());
+
+        let mut marker = ListMarker::from_str("-").unwrap();
+        marker.increment_count();
+        assert_eq!(marker, ListMarker::from_str("-").unwrap());
+    }
 }
@@ -1,7 +1,7 @@
 #![allow(missing_docs)]
 
 use clap::Parser;
-use markdown_fmt::{FormatBuilder, rewrite_markdown_with_builder};
+use markdown_fmt::{FormatBuilder, LineEnding};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
@@ -21,6 +21,29 @@ struct Cli {
     /// Should text reflow when max width is also configured.
     #[arg(short, long)]
     reflow_text: bool,
+    /// Report undefined link references, unused or duplicate link reference definitions, and
+    /// unused footnote definitions, and exit with an error if there are any.
+    #[arg(long)]
+    check_links: bool,
+    /// Also report text in square brackets, like `[this]`, that doesn't match any link reference
+    /// definition when checking links.
+    #[arg(long, requires = "check_links")]
+    check_shortcut_references: bool,
+    /// Remove link reference definitions that aren't used by any link.
+    #[arg(long)]
+    remove_unused_link_definitions: bool,
+    /// Remove footnote definitions that aren't referenced anywhere.
+    #[arg(long)]
+    remove_unused_footnote_definitions: bool,
+    /// The line ending to write. `preserve` uses the line ending that's most common in the input.
+    #[arg(long, default_value = "lf", value_parser = ["lf", "crlf", "preserve"])]
+    line_ending: String,
+    /// End the output with a newline even if the input doesn't end with one.
+    #[arg(long)]
+    insert_final_newline: bool,
+    /// Remove the UTF-8 byte order mark from the start of the input.
+    #[arg(long)]
+    remove_byte_order_mark: bool,
 }
 
 fn output_result(input: &Path, result: &str, stdout: bool) -> Result<(), anyhow::Error> {
@@ -32,6 +55,15 @@ fn output_result(input: &Path, result: &str, stdout: bool) -> Result<(), anyhow:
     }
 }
 
+/// Get the 1-based line and column of a byte offset in the input.
+fn line_and_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
 fn main() -> Result<(), anyhow::Error> {
     tracing_subscriber::registry()
         .with(fmt::layer())
@@ -39,12 +71,32 @@ fn main() -> Result<(), anyhow::Error> {
         .init();
 
     let cli = Cli::parse();
+    let line_ending = match cli.line_ending.as_str() {
+        "crlf" => LineEnding::CrLf,
+        "preserve" => LineEnding::Preserve,
+        _ => LineEnding::Lf,
+    };
 
     let input = fs::read_to_string(&cli.input)?;
     let mut builder = FormatBuilder::default();
     builder
         .max_width(cli.max_width)
-        .reflow_text(cli.reflow_text);
-    let result = rewrite_markdown_with_builder(&input, builder)?;
-    output_result(&cli.input, &result, cli.stdout)
+        .reflow_text(cli.reflow_text)
+        .remove_unused_link_definitions(cli.remove_unused_link_definitions)
+        .report_undefined_shortcut_references(cli.check_shortcut_references)
+        .remove_unused_footnote_definitions(cli.remove_unused_footnote_definitions)
+        .line_ending(line_ending)
+        .insert_final_newline(cli.insert_final_newline)
+        .remove_byte_order_mark(cli.remove_byte_order_mark);
+    let (result, diagnostics) = builder.build().format_with_diagnostics(&input)?;
+    output_result(&cli.input, &result, cli.stdout)?;
+
+    if cli.check_links && !diagnostics.is_empty() {
+        for diagnostic in &diagnostics {
+            let (line, column) = line_and_column(&input, diagnostic.range().start);
+            eprintln!("{}:{line}:{column}: {diagnostic}", cli.input.display());
+        }
+        anyhow::bail!("found {} problem(s) with links", diagnostics.len());
+    }
+    Ok(())
 }
@@ -9,7 +9,36 @@ use std::fmt::Write;
 use std::sync::OnceLock;
 use textwrap::Options as TextWrapOptions;
 
-const MARKDOWN_HARD_BREAK: &str = "  \n";
+/// Get the length of the hard break at the end of `line`, not counting the newline. Hard breaks
+/// are two spaces or an unescaped `\` at the end of a line.
+fn hard_break_len(line: &str) -> Option<usize> {
+    let backslashes = line.len() - line.trim_end_matches('\\').len();
+    if line.ends_with("  ") {
+        Some(2)
+    } else if backslashes % 2 == 1 {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// Split `text` on the hard breaks at the end of its lines. Each piece of text comes with the
+/// hard break that ends it, including the newline.
+fn split_on_hard_breaks(text: &str) -> Vec<(&str, &str)> {
+    let mut pieces = vec![];
+    let mut start = 0;
+    for (newline, _) in text.match_indices('\n') {
+        if let Some(len) = hard_break_len(&text[start..newline]) {
+            pieces.push((
+                &text[start..newline - len],
+                &text[newline - len..newline + 1],
+            ));
+            start = newline + 1;
+        }
+    }
+    pieces.push((&text[start..], ""));
+    pieces
+}
 
 /// A buffer where we write text
 #[derive(Debug, PartialEq)]
@@ -35,6 +64,11 @@ impl WriteContext<'_> for Paragraph {
                     return self.write_str(s);
                 }
             }
+            MarkdownContext::Event(Event::HardBreak) => {
+                // Written directly so that reflowing text doesn't turn the break into a space
+                self.buffer.push_str(s);
+                return Ok(());
+            }
             MarkdownContext::Tag(Tag::Link { link_type, .. }) => {
                 let closing_brace_needs_escape = || {
                     if !self.buffer.ends_with(']')
@@ -101,7 +135,12 @@ impl WriteContext<'_> for Paragraph {
 
         // Prevent the next pass from ignoring the hard break or misinterpreting `s`
         // as something other than text in a paragraph
-        if self.buffer.ends_with(MARKDOWN_HARD_BREAK) && needs_escape {
+        let ends_with_hard_break = self
+            .buffer
+            .strip_suffix('\n')
+            .and_then(hard_break_len)
+            .is_some();
+        if ends_with_hard_break && needs_escape {
             self.buffer.push('\\');
         }
 
@@ -190,10 +229,7 @@ impl Paragraph {
             .word_separator(textwrap::WordSeparator::AsciiSpace)
             .wrap_algorithm(textwrap::WrapAlgorithm::FirstFit);
 
-        let mut split_on_hard_breaks = rewrite_buffer.split(MARKDOWN_HARD_BREAK).peekable();
-
-        while let Some(text) = split_on_hard_breaks.next() {
-            let has_next = split_on_hard_breaks.peek().is_some();
+        for (text, hard_break) in split_on_hard_breaks(&rewrite_buffer) {
             let wrapped_text = if self.should_reflow_text {
                 let (text, _) = textwrap::unfill(text);
                 textwrap::fill(&text, wrap_options.clone())
@@ -201,9 +237,7 @@ impl Paragraph {
                 textwrap::fill(text, wrap_options.clone())
             };
             output_buffer.push_str(&wrapped_text);
-            if has_next {
-                output_buffer.push_str(MARKDOWN_HARD_BREAK);
-            }
+            output_buffer.push_str(hard_break);
         }
 
         output_buffer
@@ -1,6 +1,8 @@
+use crate::config::TableStyle;
 use crate::utils::unicode_str_width;
+use crate::writer::{MarkdownContext, WriteContext};
 use itertools::{EitherOrBoth, Itertools};
-use pulldown_cmark::Alignment;
+use pulldown_cmark::{Alignment, Event};
 use std::borrow::Cow;
 use std::fmt::Write;
 use unicode_segmentation::UnicodeSegmentation;
@@ -18,7 +20,8 @@ pub(super) struct TableState<'a> {
     /// Keep track of the widest cell in each column.
     /// Will use this info to align columns when rewriting
     max_column_width: Vec<usize>,
-    /// Table rows
+    /// Table rows. Rows are shorter than the header when cells are missing from the end of the
+    /// row in the source.
     body: Vec<Vec<Cow<'a, str>>>,
     /// Keep track of whether or not we're writing to the headers
     write_to_body: bool,
@@ -42,6 +45,30 @@ impl Write for TableState<'_> {
     }
 }
 
+impl WriteContext<'_> for TableState<'_> {
+    fn write_context_str(&mut self, ctx: MarkdownContext<'_, '_>, s: &str) -> std::fmt::Result {
+        match ctx {
+            MarkdownContext::Event(Event::Text(_)) | MarkdownContext::Escape => {
+                // Whitespace in table cells is collapsed when rendered, so normalize it.
+                let mut text = String::with_capacity(s.len());
+                let mut last_was_whitespace = self.current_cell().map_or(true, |cell| {
+                    cell.is_empty() || cell.ends_with(|c: char| c.is_ascii_whitespace())
+                });
+                for c in s.chars() {
+                    let is_whitespace = matches!(c, ' ' | '\t');
+                    if !(is_whitespace && last_was_whitespace) {
+                        text.push(if is_whitespace { ' ' } else { c });
+                    }
+                    last_was_whitespace = is_whitespace;
+                }
+                self.write(text.into());
+                Ok(())
+            }
+            _ => self.write_str(s),
+        }
+    }
+}
+
 impl<'a> TableState<'a> {
     pub(super) fn new(alignment: Vec<Alignment>) -> Self {
         let capacity = alignment.len();
@@ -61,7 +88,11 @@ impl<'a> TableState<'a> {
     }
 
     /// Write some values to the table state.
+    ///
+    /// Any `|` that isn't already escaped gets escaped, since it would end the cell otherwise.
     pub(super) fn write(&mut self, value: Cow<'a, str>) {
+        let last_char = self.current_cell().and_then(|cell| cell.chars().last());
+        let value = escape_pipes(value, last_char);
         if self.write_to_body {
             // push or update the body
             self.write_cell(value)
@@ -71,6 +102,16 @@ impl<'a> TableState<'a> {
         }
     }
 
+    /// The cell that we're currently writing to, if anything has been written to it yet.
+    fn current_cell(&self) -> Option<&str> {
+        let cells = if self.write_to_body {
+            self.body.last()?
+        } else {
+            &self.headers
+        };
+        cells.get(self.col_index).map(|cell| cell.as_ref())
+    }
+
     /// Allows the caller to advance the internal col_index.
     /// All subsequent calls to `write` will affect the next cell
     pub(super) fn increment_col_index(&mut self) {
@@ -85,25 +126,10 @@ impl<'a> TableState<'a> {
     }
 
     fn write_header(&mut self, text: Cow<'a, str>) {
-        let header_width = unicode_str_width(&text);
         if let Some(column_header) = self.headers.get_mut(self.col_index) {
             *column_header += text;
-
-            let current_width = self
-                .max_column_width
-                .get_mut(self.col_index)
-                .expect("should be set");
-            *current_width += header_width;
         } else {
             self.headers.push(text);
-            self.update_column_width(self.col_index, header_width);
-        }
-    }
-
-    fn update_column_width_for_alignment(&mut self) {
-        for width in self.max_column_width.iter_mut() {
-            // 3 = `---` or `:--` or `--:` or `:-:`
-            *width = std::cmp::max(*width, 3);
         }
     }
 
@@ -114,46 +140,138 @@ impl<'a> TableState<'a> {
             .expect("can only write cells after push_row called");
 
         if let Some(cell_value) = row.get_mut(self.col_index) {
-            let current_width = unicode_str_width(cell_value) + unicode_str_width(&text);
             *cell_value += text;
-            self.update_column_width(self.col_index, current_width);
         } else {
-            let cell_width = unicode_str_width(&text);
             row.push(text);
-            self.update_column_width(self.col_index, cell_width);
         }
     }
 
-    fn update_column_width(&mut self, index: usize, column_width: usize) {
-        if let Some(old_column_width) = self.max_column_width.get_mut(index) {
-            if *old_column_width < column_width {
-                *old_column_width = column_width
+    /// Trim trailing whitespace from every cell, and find the widest cell in each column.
+    fn trim_cells_and_update_column_widths(&mut self) {
+        let cells = self
+            .headers
+            .iter_mut()
+            .chain(self.body.iter_mut().flatten());
+        for cell in cells {
+            let trimmed_len = cell.trim_end().len();
+            if trimmed_len < cell.len() {
+                *cell = Cow::Owned(cell[..trimmed_len].to_owned());
+            }
+        }
+
+        let rows = std::iter::once(&self.headers).chain(self.body.iter());
+        for row in rows {
+            for (cell, width) in row.iter().zip(self.max_column_width.iter_mut()) {
+                *width = std::cmp::max(*width, unicode_str_width(cell));
             }
         }
+
+        for width in self.max_column_width.iter_mut() {
+            // 3 = `---` or `:--` or `--:` or `:-:`
+            *width = std::cmp::max(*width, 3);
+        }
     }
 
-    pub(super) fn format(mut self) -> Result<String, std::fmt::Error> {
-        // Just in case adjust the column widths for the allignemnt before formatting.
-        self.update_column_width_for_alignment();
+    /// Format the table. `max_width` is the width that's available for each line of the table
+    /// after the leading `|`. When `align_cells` is `true`, cells are padded so that their text
+    /// lines up with the column's alignment.
+    pub(super) fn format(
+        mut self,
+        style: TableStyle,
+        align_cells: bool,
+        max_width: Option<usize>,
+    ) -> Result<String, std::fmt::Error> {
+        self.trim_cells_and_update_column_widths();
+
+        let compact = match style {
+            TableStyle::Aligned => false,
+            TableStyle::Compact => true,
+            TableStyle::AlignedUnlessTooWide => {
+                max_width.is_some_and(|max_width| self.aligned_width() > max_width)
+            }
+        };
 
         let mut result = String::new();
-        self.rewrite_header(&mut result)?;
-        self.rewrite_alignment(&mut result)?;
-        self.rewrite_body(&mut result)?;
+        if compact {
+            self.rewrite_compact(&mut result)?;
+        } else {
+            self.rewrite_header(&mut result, align_cells)?;
+            self.rewrite_alignment(&mut result)?;
+            self.rewrite_body(&mut result, align_cells)?;
+        }
         Ok(result)
     }
 
-    fn write_wth_padding(buffer: &mut String, value: &str, mut size: usize) -> std::fmt::Result {
+    /// The width of each line of an aligned table, not counting the leading `|`.
+    fn aligned_width(&self) -> usize {
+        // 3 = the space on either side of the cell and the `|` that comes after it
+        self.max_column_width.iter().map(|width| width + 3).sum()
+    }
+
+    fn write_wth_padding(
+        buffer: &mut String,
+        value: &str,
+        mut size: usize,
+        alignment: Alignment,
+    ) -> std::fmt::Result {
         let offset = UnicodeSegmentation::graphemes(value, true)
             .map(|grapheme| unicode_str_width(grapheme).saturating_sub(1))
             .sum();
         size = size.saturating_sub(offset);
-        write!(buffer, " {value:<size$} |")
+        match alignment {
+            Alignment::Center => write!(buffer, " {value:^size$} |"),
+            Alignment::Right => write!(buffer, " {value:>size$} |"),
+            Alignment::Left | Alignment::None => write!(buffer, " {value:<size$} |"),
+        }
+    }
+
+    /// The alignment used to pad cells in the column at `index`.
+    fn cell_alignment(&self, index: usize, align_cells: bool) -> Alignment {
+        match self.alignment.get(index) {
+            Some(alignment) if align_cells => *alignment,
+            _ => Alignment::None,
+        }
     }
 
-    fn rewrite_header(&self, buffer: &mut String) -> std::fmt::Result {
-        for (header, width) in self.headers.iter().zip(self.max_column_width.iter()) {
-            Self::write_wth_padding(buffer, header, *width)?;
+    fn write_without_padding(buffer: &mut String, value: &str) -> std::fmt::Result {
+        if value.is_empty() {
+            write!(buffer, " |")
+        } else {
+            write!(buffer, " {value} |")
+        }
+    }
+
+    /// Write the table without padding cells, using the shortest delimiter row.
+    fn rewrite_compact(&self, buffer: &mut String) -> std::fmt::Result {
+        for header in self.headers.iter() {
+            Self::write_without_padding(buffer, header)?;
+        }
+
+        writeln!(buffer)?;
+        for alignment in self.alignment.iter() {
+            let alignment = match alignment {
+                Alignment::Center => ":-:",
+                Alignment::Left => ":-",
+                Alignment::Right => "-:",
+                Alignment::None => "-",
+            };
+            Self::write_without_padding(buffer, alignment)?;
+        }
+
+        for row in self.body.iter() {
+            writeln!(buffer)?;
+            for cell in row.iter() {
+                Self::write_without_padding(buffer, cell)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rewrite_header(&self, buffer: &mut String, align_cells: bool) -> std::fmt::Result {
+        let headers = self.headers.iter().zip(self.max_column_width.iter());
+        for (index, (header, width)) in headers.enumerate() {
+            let alignment = self.cell_alignment(index, align_cells);
+            Self::write_wth_padding(buffer, header, *width, alignment)?;
         }
         Ok(())
     }
@@ -180,21 +298,23 @@ impl<'a> TableState<'a> {
                     "-".repeat(*width)
                 }
             };
-            Self::write_wth_padding(buffer, &alignment, *width)?;
+            Self::write_wth_padding(buffer, &alignment, *width, Alignment::None)?;
         }
         Ok(())
     }
 
-    fn rewrite_body(&self, buffer: &mut String) -> std::fmt::Result {
+    fn rewrite_body(&self, buffer: &mut String, align_cells: bool) -> std::fmt::Result {
         for row in self.body.iter() {
             writeln!(buffer)?;
-            for either_or_both in row.iter().zip_longest(self.max_column_width.iter()) {
+            let cells = row.iter().zip_longest(self.max_column_width.iter());
+            for (index, either_or_both) in cells.enumerate() {
                 match either_or_both {
                     EitherOrBoth::Both(cell, width) => {
-                        Self::write_wth_padding(buffer, cell, *width)?;
+                        let alignment = self.cell_alignment(index, align_cells);
+                        Self::write_wth_padding(buffer, cell, *width, alignment)?;
                     }
                     EitherOrBoth::Right(width) => {
-                        Self::write_wth_padding(buffer, "", *width)?;
+                        Self::write_wth_padding(buffer, "", *width, Alignment::None)?;
                     }
                     EitherOrBoth::Left(_) => {
                         // There may be fewer cells in a row, but there should never be more cells.
@@ -207,3 +327,22 @@ impl<'a> TableState<'a> {
         Ok(())
     }
 }
+
+/// Escape every `|` that isn't directly preceded by a `\`. `last_char` is the character that
+/// comes right before the `text`.
+fn escape_pipes(text: Cow<'_, str>, last_char: Option<char>) -> Cow<'_, str> {
+    if !text.contains('|') {
+        return text;
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 1);
+    let mut last_char = last_char;
+    for c in text.chars() {
+        if c == '|' && last_char != Some('\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        last_char = Some(c);
+    }
+    Cow::from(escaped)
+}
@@ -18,6 +18,7 @@ impl FormatBuilder {
         let opener = "<!-- :";
         let closer = "-->";
         for l in input
+            .trim_start_matches('\u{feff}')
             .lines()
             .take_while(|l| l.starts_with(opener) && l.ends_with(closer))
         {
@@ -82,7 +83,7 @@ fn display_diff(old_filename: &str, new_filename: &str, old_text: &str, new_text
         context_size: 5,
         skipping_marker: "",
     };
-    let diff = prettydiff::diff_lines(&old_text, &new_text)
+    let diff = prettydiff::diff_lines(old_text, new_text)
         .names(old_filename, new_filename)
         .set_diff_only(true)
         .set_show_lines(true)
@@ -182,4 +183,16 @@ mod tester {
         let result = rewrite_markdown("`\u{2}\u{2}[\n\\\\\n\u{b}>* @`").unwrap();
         assert_eq!(result, "`\u{2}\u{2}[\n\\\\\n\u{b}>* @`");
     }
+
+    #[test]
+    fn diagnostics_point_into_crlf_input() {
+        let input = "\u{feff}Text\r\n\r\n[unused]: /a\r\n[other\r\nlabel]: /b\r\n";
+        let formatter = crate::FormatBuilder::default().build();
+        let (_, diagnostics) = formatter.format_with_diagnostics(input).unwrap();
+        let ranges = diagnostics
+            .iter()
+            .map(|d| &input[d.range()])
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, ["[unused]: /a", "[other\r\nlabel]: /b"]);
+    }
 }
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use itertools::Itertools;
 use std::iter::Iterator;
 use unicode_width::UnicodeWidthStr;
 
@@ -128,6 +129,83 @@ pub(crate) fn split_lines(s: &str) -> impl Iterator<Item = &str> {
     }
 }
 
+/// Get the line ending that's used the most in `s`. Ties go to `\n`.
+pub(crate) fn most_common_line_ending(s: &str) -> &'static str {
+    let crlf_count = s.matches("\r\n").count();
+    let lf_count = s.matches('\n').count() - crlf_count;
+    if crlf_count > lf_count { "\r\n" } else { "\n" }
+}
+
+/// Replace every `\n`, `\r\n`, and `\r` in `s` with `line_ending`.
+pub(crate) fn replace_line_endings(s: &str, line_ending: &str) -> String {
+    let mut output = split_lines(s).join(line_ending);
+    if s.ends_with(['\r', '\n']) {
+        output.push_str(line_ending);
+    }
+    output
+}
+
+/// Offsets of the newlines in `replace_line_endings(s, "\n")` that were written as `\r\n` in `s`.
+///
+/// Used to map offsets in the normalized text back to offsets in `s`.
+pub(crate) fn crlf_offsets(s: &str) -> Vec<usize> {
+    s.match_indices("\r\n")
+        .enumerate()
+        .map(|(removed, (offset, _))| offset - removed)
+        .collect()
+}
+
+/// Check if the input is a single line that would be parsed as a thematic break.
+/// For example, `---`, `***`, or `_ _ _`.
+pub(crate) fn is_thematic_break(s: &str) -> bool {
+    let s = s.trim_matches([' ', '\t']);
+    let Some(rule_char @ ('-' | '*' | '_')) = s.chars().next() else {
+        return false;
+    };
+
+    s.chars().all(|c| matches!(c, ' ' | '\t') || c == rule_char)
+        && s.chars().filter(|c| *c == rule_char).count() >= 3
+}
+
+/// CommonMark expands tabs to the next multiple of 4 columns.
+const TAB_STOP: usize = 4;
+
+/// The column that a tab at `column` advances to.
+pub(crate) fn next_tab_stop(column: usize) -> usize {
+    column - column % TAB_STOP + TAB_STOP
+}
+
+/// Remove leading spaces and tabs from `s`, which starts at `column`, without going past
+/// `max_column`. A tab that would go past `max_column` isn't removed.
+///
+/// Returns what's left of `s` along with the column it starts at.
+pub(crate) fn trim_start_columns(s: &str, mut column: usize, max_column: usize) -> (&str, usize) {
+    let mut offset = 0;
+    for c in s.chars() {
+        let next_column = match c {
+            ' ' => column + 1,
+            '\t' => next_tab_stop(column),
+            _ => break,
+        };
+        if next_column > max_column {
+            break;
+        }
+        column = next_column;
+        offset += 1;
+    }
+    (&s[offset..], column)
+}
+
+/// Replace the tabs in the leading whitespace of `s`, which starts at `column`, with spaces.
+pub(crate) fn expand_leading_tabs(s: &str, column: usize) -> Cow<'_, str> {
+    let (rest, end_column) = trim_start_columns(s, column, usize::MAX);
+    let leading_whitespace = &s[..s.len() - rest.len()];
+    if !leading_whitespace.contains('\t') {
+        return s.into();
+    }
+    format!("{}{rest}", get_spaces(end_column - column)).into()
+}
+
 #[test]
 fn make_sure_sequence_ends_on_escape_works() {
     // Sequences that end on an unescaped backslash
@@ -180,3 +258,41 @@ fn test_split_lines() {
         assert_eq!(iter.next(), None);
     }
 }
+
+#[test]
+fn check_is_thematic_break() {
+    assert!(is_thematic_break("---"));
+    assert!(is_thematic_break("* * *"));
+    assert!(is_thematic_break("  ___  "));
+    assert!(is_thematic_break("----------"));
+
+    assert!(!is_thematic_break("--"));
+    assert!(!is_thematic_break("-*-"));
+    assert!(!is_thematic_break("==="));
+    assert!(!is_thematic_break("---\n---"));
+    assert!(!is_thematic_break(""));
+}
+
+#[test]
+fn tabs_are_expanded_to_the_next_tab_stop() {
+    assert_eq!(trim_start_columns("\t\tx", 0, 4), ("\tx", 4));
+    assert_eq!(trim_start_columns("  \tx", 0, 3), ("\tx", 2));
+    assert_eq!(trim_start_columns(" \t x", 1, usize::MAX), ("x", 5));
+    assert_eq!(expand_leading_tabs("\t<p>\t</p>", 0), "    <p>\t</p>");
+    assert_eq!(expand_leading_tabs(" \tx", 2), "  x");
+    assert_eq!(expand_leading_tabs("  x", 0), "  x");
+}
+
+#[test]
+fn line_endings_are_replaced() {
+    assert_eq!(most_common_line_ending("a\r\nb\r\nc\n"), "\r\n");
+    assert_eq!(most_common_line_ending("a\r\nb\n"), "\n");
+    assert_eq!(most_common_line_ending("a"), "\n");
+    assert_eq!(
+        replace_line_endings("a\nb\r\nc\rd", "\r\n"),
+        "a\r\nb\r\nc\r\nd"
+    );
+    assert_eq!(replace_line_endings("a\r\n\r\n", "\n"), "a\n\n");
+    assert_eq!(replace_line_endings("\n", "\r\n"), "\r\n");
+    assert_eq!(crlf_offsets("a\r\nb\rc\r\n\r\n"), [1, 5, 6]);
+}
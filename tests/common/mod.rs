@@ -1,11 +1,12 @@
-/// Collection of common functions / macros used for generating tests
+//! Collection of common functions / macros used for generating tests
 
 #[allow(dead_code)]
 pub fn check_formatted_markdown<'a>(
+    formatter: &markdown_fmt::MarkdownFormatter,
     input: &'a str,
     expected_output: &str,
 ) -> std::borrow::Cow<'a, str> {
-    let formatted = markdown_fmt::rewrite_markdown(input).expect("formatting won't fail");
+    let formatted = formatter.format(input).expect("formatting won't fail");
     assert_eq!(formatted, expected_output);
     formatted.into()
 }
@@ -15,11 +16,16 @@ macro_rules! test {
     ($input:expr) => {
         test!($input, $input)
     };
-    ($input:expr, $output:expr) => {{
-        let formatted = $crate::common::check_formatted_markdown($input, $output);
+    ($input:expr, $output:expr) => {
+        test!(markdown_fmt::FormatBuilder::default().build(); $input, $output)
+    };
+    // Format with a `MarkdownFormatter` instead of the default configuration
+    ($formatter:expr; $input:expr, $output:expr) => {{
+        let formatter = $formatter;
+        let formatted = $crate::common::check_formatted_markdown(&formatter, $input, $output);
         if $input != $output {
             // Perform an idempotency check on the formatted markdown
-            $crate::common::check_formatted_markdown(&formatted, &formatted);
+            $crate::common::check_formatted_markdown(&formatter, &formatted, &formatted);
         }
         formatted
     }};
@@ -31,7 +37,13 @@ macro_rules! test_identical_markdown_events {
         test_identical_markdown_events!($input, $input)
     };
     ($input:expr, $output:expr) => {
-        let formatted = $crate::test!($input, $output);
+        test_identical_markdown_events!(
+            markdown_fmt::FormatBuilder::default().build(); $input, $output
+        )
+    };
+    // Format with a `MarkdownFormatter` instead of the default configuration
+    ($formatter:expr; $input:expr, $output:expr) => {
+        let formatted = $crate::test!($formatter; $input, $output);
 
         let options = markdown_fmt::pulldown_cmark_options!();
 